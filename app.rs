@@ -0,0 +1,350 @@
+use crate::game::{Difficulty, GameEvent, GameMode, GameState, Input, Settings};
+use crate::scores::{Entry, HighScores, INITIALS_LEN};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+const LEVEL_COMPLETE_TIME: u64 = 2000; // Milliseconds
+
+#[derive(Clone, Copy)]
+pub enum PauseItem {
+    Resume,
+    Restart,
+    Settings,
+    Quit,
+}
+
+impl PauseItem {
+    pub const ALL: [PauseItem; 4] = [
+        PauseItem::Resume,
+        PauseItem::Restart,
+        PauseItem::Settings,
+        PauseItem::Quit,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PauseItem::Resume => "Resume",
+            PauseItem::Restart => "Restart",
+            PauseItem::Settings => "Settings",
+            PauseItem::Quit => "Quit",
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum TitleItem {
+    Play,
+    Mode,
+    HighScores,
+    Settings,
+    Help,
+    Quit,
+}
+
+impl TitleItem {
+    pub const ALL: [TitleItem; 6] = [
+        TitleItem::Play,
+        TitleItem::Mode,
+        TitleItem::HighScores,
+        TitleItem::Settings,
+        TitleItem::Help,
+        TitleItem::Quit,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TitleItem::Play => "Play",
+            TitleItem::Mode => "Mode",
+            TitleItem::HighScores => "High scores",
+            TitleItem::Settings => "Settings",
+            TitleItem::Help => "Help",
+            TitleItem::Quit => "Quit",
+        }
+    }
+}
+
+/// Moves a menu highlight up or down, wrapping at either end. Other keys leave it alone.
+fn menu_move(selected: usize, len: usize, code: KeyCode) -> usize {
+    match code {
+        KeyCode::Up | KeyCode::Char('w') => (selected + len - 1) % len,
+        KeyCode::Down | KeyCode::Char('s') => (selected + 1) % len,
+        _ => selected,
+    }
+}
+
+/// The difficulty after (or before, going `back`) the current one, keeping board and mode.
+/// Settings given on the command line take the custom slot after the presets; without them
+/// only the presets cycle.
+fn cycle_difficulty(current: &Settings, custom: Option<Settings>, back: bool) -> Settings {
+    let choices: Vec<Difficulty> = Difficulty::ALL
+        .iter()
+        .copied()
+        .filter(|&difficulty| difficulty != Difficulty::Custom || custom.is_some())
+        .collect();
+    let index = choices
+        .iter()
+        .position(|&difficulty| difficulty == current.difficulty)
+        .unwrap_or(0);
+    let index = if back {
+        (index + choices.len() - 1) % choices.len()
+    } else {
+        (index + 1) % choices.len()
+    };
+    let mut settings = match (choices[index], custom) {
+        (Difficulty::Custom, Some(custom)) => custom,
+        (difficulty, _) => Settings::for_difficulty(difficulty),
+    };
+    settings.board = current.board;
+    settings.mode = current.mode;
+    settings
+}
+
+/// Which screen the player is looking at. Only `Playing` advances the simulation.
+pub enum Screen {
+    Title { selected: usize },      // Index into `TitleItem::ALL`
+    ModeSelect { selected: usize }, // Index into `GameMode::ALL`
+    HighScores,
+    Help,
+    Playing,
+    Paused { selected: usize }, // Index into `PauseItem::ALL`
+    Settings { return_to: Box<Screen> },
+    ConfirmQuit { return_to: Box<Screen> },
+    LevelComplete { ticks_left: u64 },
+    EnterInitials { initials: String },
+    GameOver,
+}
+
+/// A game in progress together with the screen it is shown through.
+pub struct Session {
+    pub screen: Screen,
+    pub game: GameState,
+    pub scores: HighScores,
+    pub warning: Option<String>, // Reported once the terminal is restored
+    custom: Option<Settings>,    // Command-line settings, kept while other presets are tried
+    fixed_seed: bool,            // Whether every new game replays the `--seed` board
+}
+
+impl Session {
+    pub fn new(game: GameState, scores: HighScores, fixed_seed: bool) -> Self {
+        let custom = (game.settings.difficulty == Difficulty::Custom).then_some(game.settings);
+        let mut session = Session {
+            screen: Screen::Title { selected: 0 },
+            game,
+            scores,
+            warning: None,
+            custom,
+            fixed_seed,
+        };
+        session.sync_high_score();
+        session
+    }
+
+    /// Points the HUD's high score at the saved table for the current difficulty and mode.
+    fn sync_high_score(&mut self) {
+        let settings = &self.game.settings;
+        self.game.high_score = self.scores.best(settings.difficulty, settings.mode);
+    }
+
+    /// Starts the next game on a fresh board, unless one seed was asked for throughout.
+    /// Restarting from the pause menu replays the current board instead.
+    fn new_game(&mut self) {
+        let seed = if self.fixed_seed {
+            self.game.seed
+        } else {
+            rand::random()
+        };
+        self.game.reseed(seed);
+    }
+
+    fn record_score(&mut self, initials: String) {
+        let entry = Entry {
+            difficulty: self.game.settings.difficulty,
+            mode: self.game.settings.mode,
+            initials,
+            score: self.game.frog.score,
+            level: self.game.level,
+            seed: self.game.seed,
+        };
+        self.scores.insert(entry);
+        if let Err(e) = self.scores.save() {
+            self.warning = Some(format!("Failed to save high scores: {}", e));
+        }
+    }
+
+    /// Freezes play, or the countdown into it, behind the pause menu. Other screens are
+    /// already still.
+    pub fn pause(&mut self) {
+        if let Screen::Playing | Screen::LevelComplete { .. } = self.screen {
+            self.screen = Screen::Paused { selected: 0 };
+        }
+    }
+
+    /// Feeds one key press to the current screen, collecting frog moves into `inputs`.
+    /// While the terminal cannot `fit` the board, anything that would resume play pauses
+    /// instead. Returns false once the player has chosen to quit.
+    pub fn handle_key(&mut self, key: KeyEvent, inputs: &mut Vec<Input>, fits: bool) -> bool {
+        let KeyEvent { code, modifiers } = key;
+        // Raw mode swallows SIGINT, so Ctrl+C arrives as a key.
+        if code == KeyCode::Char('c') && modifiers.contains(KeyModifiers::CONTROL) {
+            return false;
+        }
+
+        let screen = std::mem::replace(&mut self.screen, Screen::Playing);
+        self.screen = match (screen, code) {
+            (Screen::ConfirmQuit { .. }, KeyCode::Char('y')) => return false,
+            (Screen::ConfirmQuit { return_to }, KeyCode::Char('n') | KeyCode::Esc) => *return_to,
+            (screen @ Screen::ConfirmQuit { .. }, _) => screen,
+
+            // Typing initials comes before the shortcuts below so every letter can be used.
+            (Screen::EnterInitials { mut initials }, KeyCode::Char(ch))
+                if ch.is_ascii_alphanumeric() && initials.len() < INITIALS_LEN =>
+            {
+                initials.push(ch.to_ascii_uppercase());
+                Screen::EnterInitials { initials }
+            }
+            (Screen::EnterInitials { mut initials }, KeyCode::Backspace) => {
+                initials.pop();
+                Screen::EnterInitials { initials }
+            }
+            (Screen::EnterInitials { initials }, KeyCode::Enter) if !initials.is_empty() => {
+                self.record_score(initials);
+                Screen::GameOver
+            }
+            (Screen::EnterInitials { .. }, KeyCode::Esc) => Screen::GameOver,
+            (screen @ Screen::EnterInitials { .. }, _) => screen,
+
+            (screen, KeyCode::Char('q')) => Screen::ConfirmQuit {
+                return_to: Box::new(screen),
+            },
+
+            (Screen::Title { selected }, KeyCode::Enter | KeyCode::Char(' ')) => {
+                match TitleItem::ALL[selected] {
+                    TitleItem::Play => {
+                        self.new_game();
+                        Screen::Playing
+                    }
+                    TitleItem::Mode => Screen::ModeSelect {
+                        selected: GameMode::ALL
+                            .iter()
+                            .position(|&mode| mode == self.game.settings.mode)
+                            .unwrap_or(0),
+                    },
+                    TitleItem::HighScores => Screen::HighScores,
+                    TitleItem::Settings => Screen::Settings {
+                        return_to: Box::new(Screen::Title { selected }),
+                    },
+                    TitleItem::Help => Screen::Help,
+                    TitleItem::Quit => return false,
+                }
+            }
+            (Screen::Title { selected }, code) => Screen::Title {
+                selected: menu_move(selected, TitleItem::ALL.len(), code),
+            },
+
+            (Screen::ModeSelect { selected }, KeyCode::Enter | KeyCode::Char(' ')) => {
+                self.game.settings.mode = GameMode::ALL[selected];
+                self.sync_high_score();
+                Screen::Title { selected: 0 }
+            }
+            (Screen::ModeSelect { .. }, KeyCode::Esc) => Screen::Title { selected: 0 },
+            (Screen::ModeSelect { selected }, code) => Screen::ModeSelect {
+                selected: menu_move(selected, GameMode::ALL.len(), code),
+            },
+
+            (Screen::HighScores | Screen::Help, KeyCode::Esc | KeyCode::Enter) => {
+                Screen::Title { selected: 0 }
+            }
+
+            (Screen::Playing, KeyCode::Char('p') | KeyCode::Esc) => Screen::Paused { selected: 0 },
+            (Screen::Playing, code) => {
+                match code {
+                    KeyCode::Char('w') | KeyCode::Up => inputs.push(Input::Up),
+                    KeyCode::Char('s') | KeyCode::Down => inputs.push(Input::Down),
+                    KeyCode::Char('a') | KeyCode::Left => inputs.push(Input::Left),
+                    KeyCode::Char('d') | KeyCode::Right => inputs.push(Input::Right),
+                    _ => {}
+                }
+                Screen::Playing
+            }
+
+            (Screen::Paused { .. }, KeyCode::Char('p') | KeyCode::Esc) => Screen::Playing,
+            (Screen::Paused { selected }, KeyCode::Enter) => match PauseItem::ALL[selected] {
+                PauseItem::Resume => Screen::Playing,
+                PauseItem::Restart => {
+                    self.game.restart();
+                    Screen::Playing
+                }
+                PauseItem::Settings => Screen::Settings {
+                    return_to: Box::new(Screen::Paused { selected }),
+                },
+                PauseItem::Quit => return false,
+            },
+            (Screen::Paused { selected }, code) => Screen::Paused {
+                selected: menu_move(selected, PauseItem::ALL.len(), code),
+            },
+
+            (Screen::Settings { return_to }, KeyCode::Esc | KeyCode::Enter) => *return_to,
+            // Difficulty can only change between games, from the title menu.
+            (Screen::Settings { return_to }, KeyCode::Left | KeyCode::Right)
+                if matches!(*return_to, Screen::Title { .. }) =>
+            {
+                let settings =
+                    cycle_difficulty(&self.game.settings, self.custom, code == KeyCode::Left);
+                self.game.reconfigure(settings);
+                self.sync_high_score();
+                Screen::Settings { return_to }
+            }
+
+            (Screen::LevelComplete { .. }, KeyCode::Enter | KeyCode::Char(' ')) => Screen::Playing,
+
+            (Screen::GameOver, KeyCode::Char('r')) => {
+                self.new_game();
+                Screen::Playing
+            }
+            (Screen::GameOver, KeyCode::Char('t')) => {
+                self.game.restart();
+                Screen::Title { selected: 0 }
+            }
+
+            (screen, _) => screen,
+        };
+        if !fits {
+            self.pause();
+        }
+        true
+    }
+
+    /// Advances whatever the current screen animates by one tick. Nothing moves while the
+    /// terminal cannot `fit` the board, as the player could not see it.
+    pub fn tick(&mut self, inputs: &[Input], fits: bool) {
+        if !fits {
+            return;
+        }
+        match self.screen {
+            Screen::Playing => {
+                let events = self.game.step(inputs);
+                if events.contains(&GameEvent::GameOver) {
+                    let settings = &self.game.settings;
+                    let score = self.game.frog.score;
+                    self.screen =
+                        if self
+                            .scores
+                            .qualifies(settings.difficulty, settings.mode, score)
+                        {
+                            Screen::EnterInitials {
+                                initials: String::new(),
+                            }
+                        } else {
+                            Screen::GameOver
+                        };
+                } else if events.contains(&GameEvent::LevelCleared) {
+                    self.screen = Screen::LevelComplete {
+                        ticks_left: LEVEL_COMPLETE_TIME / self.game.settings.frame_rate,
+                    };
+                }
+            }
+            Screen::LevelComplete { ticks_left: 0 } => self.screen = Screen::Playing,
+            Screen::LevelComplete { ref mut ticks_left } => *ticks_left -= 1,
+            _ => {}
+        }
+    }
+}
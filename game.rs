@@ -0,0 +1,1452 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+pub const DEFAULT_WIDTH: u16 = 20;
+pub const DEFAULT_HEIGHT: u16 = 10;
+pub const MIN_WIDTH: u16 = 10;
+pub const MAX_WIDTH: u16 = 200;
+pub const MIN_HEIGHT: u16 = 6; // Goal, river, median, road and start rows all need room
+pub const MAX_HEIGHT: u16 = 60;
+pub const GOAL_ROW: u16 = 0;
+pub const NUM_BAYS: usize = 5;
+const MIN_GAP: u16 = 2; // Free cells kept between obstacles in a lane
+const BIG_HAZARD_ODDS: u32 = 4; // One lane in this many carries two-row hazards
+const RACING_CAR_ODDS: u32 = 4; // One single-row lane in this many is a racing lane
+const BULLDOZER_ODDS: u32 = 3; // Of the remaining lanes, one in this many has bulldozers
+const RACING_CAR_BOOST: i16 = 2; // How much faster than the lane limit racing cars go
+const DIVING_TURTLE_ODDS: u32 = 3; // One turtle group in this many dives, once levels allow
+const CROCODILE_ODDS: u32 = 4; // One log in this many is a crocodile, once levels allow
+const RIDING_SNAKE_ODDS: u32 = 2; // Chance that a snake also rides one of the logs
+const SNAKE_WIDTH: u16 = 2;
+const SNAKE_STEP_TIME: u64 = 300; // Milliseconds per cell
+const OTTER_STEP_TIME: u64 = 150; // Milliseconds per cell
+const OTTER_REST_TIME: u64 = 5000; // Milliseconds between attacks
+
+// Diving turtle cycle, in milliseconds.
+const SURFACED_TIME: u64 = 4000;
+const SINKING_TIME: u64 = 1200;
+const SUBMERGED_TIME: u64 = 1600;
+const DIVE_CYCLE: u64 = SURFACED_TIME + SINKING_TIME + SUBMERGED_TIME;
+
+/// How one level departs from the difficulty preset.
+pub struct LevelStep {
+    extra_obstacles: usize,
+    extra_speed: i16,
+    trucks: bool, // Whether two-row lanes of trucks can appear
+    racing_cars: bool,
+    diving_turtles: bool,
+    snakes: bool,
+    crocodiles: bool,
+    otters: bool,
+    pub news: &'static str, // Announced as the level starts
+}
+
+/// The level progression, from level 1 on. Levels past the end repeat the last step.
+const LEVEL_CURVE: [LevelStep; 8] = [
+    LevelStep {
+        extra_obstacles: 0,
+        extra_speed: 0,
+        trucks: false,
+        racing_cars: false,
+        diving_turtles: false,
+        snakes: false,
+        crocodiles: false,
+        otters: false,
+        news: "",
+    },
+    LevelStep {
+        extra_obstacles: 1,
+        extra_speed: 0,
+        trucks: true,
+        racing_cars: false,
+        diving_turtles: false,
+        snakes: false,
+        crocodiles: false,
+        otters: false,
+        news: "Trucks on the road",
+    },
+    LevelStep {
+        extra_obstacles: 2,
+        extra_speed: 0,
+        trucks: true,
+        racing_cars: false,
+        diving_turtles: true,
+        snakes: false,
+        crocodiles: false,
+        otters: false,
+        news: "Diving turtles",
+    },
+    LevelStep {
+        extra_obstacles: 2,
+        extra_speed: 1,
+        trucks: true,
+        racing_cars: true,
+        diving_turtles: true,
+        snakes: false,
+        crocodiles: false,
+        otters: false,
+        news: "Racing cars",
+    },
+    LevelStep {
+        extra_obstacles: 3,
+        extra_speed: 1,
+        trucks: true,
+        racing_cars: true,
+        diving_turtles: true,
+        snakes: true,
+        crocodiles: false,
+        otters: false,
+        news: "Snakes",
+    },
+    LevelStep {
+        extra_obstacles: 4,
+        extra_speed: 1,
+        trucks: true,
+        racing_cars: true,
+        diving_turtles: true,
+        snakes: true,
+        crocodiles: true,
+        otters: false,
+        news: "Crocodiles",
+    },
+    LevelStep {
+        extra_obstacles: 5,
+        extra_speed: 1,
+        trucks: true,
+        racing_cars: true,
+        diving_turtles: true,
+        snakes: true,
+        crocodiles: true,
+        otters: true,
+        news: "Otters",
+    },
+    LevelStep {
+        extra_obstacles: 6,
+        extra_speed: 2,
+        trucks: true,
+        racing_cars: true,
+        diving_turtles: true,
+        snakes: true,
+        crocodiles: true,
+        otters: true,
+        news: "Gridlock",
+    },
+];
+
+pub fn level_step(level: u32) -> &'static LevelStep {
+    let index = (level.max(1) - 1) as usize;
+    &LEVEL_CURVE[index.min(LEVEL_CURVE.len() - 1)]
+}
+
+// Points table, after the arcade original.
+const STEP_POINTS: u32 = 10;
+const GOAL_POINTS: u32 = 50;
+const TIME_BONUS_POINTS: u32 = 10; // Per remaining second
+
+/// Dimensions of the play field and where its zones fall.
+#[derive(Clone, Copy)]
+pub struct Board {
+    pub width: u16,
+    pub height: u16,
+}
+
+impl Board {
+    pub fn new(width: u16, height: u16) -> Self {
+        Board { width, height }
+    }
+
+    pub fn start_row(&self) -> u16 {
+        self.height - 1
+    }
+
+    /// Safe strip between the river above and the road below.
+    pub fn median_row(&self) -> u16 {
+        self.height / 2
+    }
+
+    pub fn is_river_row(&self, y: u16) -> bool {
+        y > GOAL_ROW && y < self.median_row()
+    }
+
+    /// Which home bay, if any, column `x` of the goal row opens into. The row is split into
+    /// one slot per bay, each a bay flanked by hedge; columns left over at the right edge
+    /// are hedge too.
+    pub fn bay_at(&self, x: u16) -> Option<usize> {
+        let slot = self.width / NUM_BAYS as u16;
+        let bay = (x / slot) as usize;
+        if bay >= NUM_BAYS {
+            return None;
+        }
+        let bay_width = (slot / 2).max(1);
+        let start = bay as u16 * slot + (slot - bay_width) / 2;
+        (start..start + bay_width).contains(&x).then_some(bay)
+    }
+
+    /// Brings a column that has run off either edge back round onto the board.
+    fn wrap(&self, x: i32) -> u16 {
+        x.rem_euclid(self.width as i32) as u16
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+    Custom,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Medium,
+        Difficulty::Hard,
+        Difficulty::Custom,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "easy" => Some(Difficulty::Easy),
+            "medium" => Some(Difficulty::Medium),
+            "hard" => Some(Difficulty::Hard),
+            "custom" => Some(Difficulty::Custom),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+            Difficulty::Custom => "custom",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum GameMode {
+    /// Lives run out and levels get harder.
+    Classic,
+    /// Deaths only send the frog back to the start, for learning a board. As a practice
+    /// game never ends, it is never scored.
+    Practice,
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 2] = [GameMode::Classic, GameMode::Practice];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "classic" => Some(GameMode::Classic),
+            "practice" => Some(GameMode::Practice),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            GameMode::Classic => "classic",
+            GameMode::Practice => "practice",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            GameMode::Classic => "Lives run out",
+            GameMode::Practice => "Unlimited lives, unscored",
+        }
+    }
+
+    /// Whether games in this mode can make the high score tables.
+    pub fn keeps_scores(self) -> bool {
+        match self {
+            GameMode::Classic => true,
+            GameMode::Practice => false,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Settings {
+    pub difficulty: Difficulty,
+    pub mode: GameMode,
+    pub board: Board,
+    pub num_obstacles: usize,
+    pub max_speed: i16,
+    pub lives: u8,
+    pub frame_rate: u64, // Milliseconds
+    pub round_time: u64, // Seconds
+    pub extra_life_score: u32,
+}
+
+impl Settings {
+    pub fn for_difficulty(difficulty: Difficulty) -> Self {
+        match difficulty {
+            Difficulty::Easy => Settings {
+                difficulty,
+                mode: GameMode::Classic,
+                board: Board::new(DEFAULT_WIDTH, DEFAULT_HEIGHT),
+                num_obstacles: 3,
+                max_speed: 1,
+                lives: 5,
+                frame_rate: 150,
+                round_time: 60,
+                extra_life_score: 10_000,
+            },
+            Difficulty::Hard => Settings {
+                difficulty,
+                mode: GameMode::Classic,
+                board: Board::new(DEFAULT_WIDTH, DEFAULT_HEIGHT),
+                num_obstacles: 8,
+                max_speed: 3,
+                lives: 2,
+                frame_rate: 70,
+                round_time: 30,
+                extra_life_score: 10_000,
+            },
+            // Custom games start from the medium preset.
+            Difficulty::Medium | Difficulty::Custom => Settings {
+                difficulty,
+                mode: GameMode::Classic,
+                board: Board::new(DEFAULT_WIDTH, DEFAULT_HEIGHT),
+                num_obstacles: 5,
+                max_speed: 2,
+                lives: 3,
+                frame_rate: 100,
+                round_time: 45,
+                extra_life_score: 10_000,
+            },
+        }
+    }
+}
+
+pub struct Frog {
+    board: Board,
+    pub x: u16,
+    pub y: u16,
+    pub lives: u8,
+    pub score: u32,
+    furthest_row: u16,
+    extra_life_awarded: bool,
+}
+
+impl Frog {
+    /// Places a new frog in the middle of the start row.
+    fn new(board: Board, lives: u8) -> Self {
+        let mut frog = Frog {
+            board,
+            x: 0,
+            y: 0,
+            lives,
+            score: 0,
+            furthest_row: 0,
+            extra_life_awarded: false,
+        };
+        frog.reset_position();
+        frog
+    }
+
+    fn reset_position(&mut self) {
+        self.x = self.board.width / 2;
+        self.y = self.board.start_row();
+        self.furthest_row = self.y;
+    }
+
+    fn lose_life(&mut self) {
+        self.lives = self.lives.saturating_sub(1);
+        self.reset_position();
+    }
+
+    /// Carries the frog sideways by `speed` cells. Returns false, leaving the frog where it
+    /// was, if that would take it off the board.
+    fn drift(&mut self, speed: i16) -> bool {
+        let x = self.x as i32 + speed as i32;
+        if x < 0 || x >= self.board.width as i32 {
+            return false;
+        }
+        self.x = x as u16;
+        true
+    }
+
+    /// Adds points, handing out the single bonus life once the score passes `extra_life_score`.
+    /// Returns true if this awarded the bonus life.
+    fn add_points(&mut self, points: u32, extra_life_score: u32) -> bool {
+        self.score += points;
+        if !self.extra_life_awarded && self.score >= extra_life_score {
+            self.extra_life_awarded = true;
+            self.lives = self.lives.saturating_add(1);
+            return true;
+        }
+        false
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.lives == 0
+    }
+
+    fn hop(&mut self, input: Input) {
+        match input {
+            Input::Up => self.move_up(),
+            Input::Down => self.move_down(),
+            Input::Left => self.move_left(),
+            Input::Right => self.move_right(),
+        }
+    }
+
+    fn move_up(&mut self) {
+        if self.y > 0 {
+            self.y -= 1;
+        }
+    }
+
+    fn move_down(&mut self) {
+        if self.y < self.board.start_row() {
+            self.y += 1;
+        }
+    }
+
+    fn move_left(&mut self) {
+        if self.x > 0 {
+            self.x -= 1;
+        }
+    }
+
+    fn move_right(&mut self) {
+        if self.x < self.board.width - 1 {
+            self.x += 1;
+        }
+    }
+}
+
+pub struct Obstacle {
+    x: u16,
+    y: u16,
+    width: u16,
+    height: u16,
+    speed: i16,
+    pub kind: VehicleKind,
+}
+
+impl Obstacle {
+    fn new(x: u16, y: u16, height: u16, speed: i16, kind: VehicleKind) -> Self {
+        Obstacle {
+            x,
+            y,
+            width: kind.width(),
+            height,
+            speed,
+            kind,
+        }
+    }
+
+    pub fn heading_right(&self) -> bool {
+        self.speed > 0
+    }
+
+    /// Board columns covered by the obstacle, wrapping around the right edge.
+    pub fn columns(&self, board: Board) -> impl Iterator<Item = u16> + '_ {
+        (0..self.width.min(board.width)).map(move |dx| board.wrap((self.x + dx) as i32))
+    }
+
+    /// Board rows covered by the obstacle, clipped to the play field.
+    pub fn rows(&self, board: Board) -> std::ops::Range<u16> {
+        self.y..(self.y + self.height).min(board.height)
+    }
+
+    fn occupies(&self, x: u16, y: u16, board: Board) -> bool {
+        let dx = board.wrap(x as i32 - self.x as i32);
+        dx < self.width && y >= self.y && y < self.y + self.height
+    }
+
+    /// Like `occupies`, but also counting the cells the obstacle passed over during its
+    /// last move, so fast vehicles can't jump clean over the frog.
+    fn swept(&self, x: u16, y: u16, board: Board) -> bool {
+        let start = if self.speed > 0 {
+            self.x as i32 - self.speed as i32
+        } else {
+            self.x as i32
+        };
+        let dx = board.wrap(x as i32 - start);
+        dx < self.width + self.speed.unsigned_abs() && y >= self.y && y < self.y + self.height
+    }
+
+    fn r#move(&mut self, board: Board) {
+        self.x = board.wrap(self.x as i32 + self.speed as i32);
+    }
+}
+
+/// What drives along a lane. Each kind has its own length and pace.
+#[derive(Clone, Copy)]
+pub enum VehicleKind {
+    Car,
+    Truck,
+    Bulldozer,
+    RacingCar,
+}
+
+impl VehicleKind {
+    fn width(self) -> u16 {
+        match self {
+            VehicleKind::Car => 3,
+            VehicleKind::Truck => 4,
+            VehicleKind::Bulldozer => 3,
+            VehicleKind::RacingCar => 2,
+        }
+    }
+
+    /// Lane speed, ignoring direction, for a level whose lanes go up to `max_speed`.
+    fn speed(self, max_speed: i16, rng: &mut impl Rng) -> i16 {
+        match self {
+            VehicleKind::Car => rng.gen_range(1..=max_speed),
+            VehicleKind::Truck => rng.gen_range(1..=(max_speed + 1) / 2),
+            VehicleKind::Bulldozer => 1,
+            VehicleKind::RacingCar => max_speed + RACING_CAR_BOOST,
+        }
+    }
+}
+
+/// What touching one cell of a creature does to the frog.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Contact {
+    Safe,
+    Deadly,
+}
+
+#[derive(Clone, Copy)]
+pub enum PlatformKind {
+    Log,
+    Turtle,
+    /// Rides like a log, but its head, at the leading end, bites.
+    Crocodile,
+}
+
+/// Where a diving turtle group is in its cycle. Sinking turtles still hold the frog up;
+/// submerged ones don't.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum DivePhase {
+    Surfaced,
+    Sinking,
+    Submerged,
+}
+
+/// Something floating in the river that the frog can ride on.
+pub struct Platform {
+    x: u16,
+    pub y: u16,
+    width: u16,
+    speed: i16,
+    pub kind: PlatformKind,
+    dive_clock: Option<u64>, // Milliseconds into `DIVE_CYCLE`, for turtles that dive
+}
+
+impl Platform {
+    fn new(x: u16, y: u16, width: u16, speed: i16, kind: PlatformKind) -> Self {
+        Platform {
+            x,
+            y,
+            width,
+            speed,
+            kind,
+            dive_clock: None,
+        }
+    }
+
+    pub fn dive_phase(&self) -> DivePhase {
+        match self.dive_clock {
+            Some(clock) if clock >= SURFACED_TIME + SINKING_TIME => DivePhase::Submerged,
+            Some(clock) if clock >= SURFACED_TIME => DivePhase::Sinking,
+            _ => DivePhase::Surfaced,
+        }
+    }
+
+    /// Board columns covered by the platform, wrapping around the right edge, each with
+    /// what landing on it does.
+    pub fn cells(&self, board: Board) -> impl Iterator<Item = (u16, Contact)> + '_ {
+        let width = self.width.min(board.width);
+        let head = if self.speed > 0 { width - 1 } else { 0 };
+        (0..width).map(move |dx| {
+            let contact = match self.kind {
+                PlatformKind::Crocodile if dx == head => Contact::Deadly,
+                _ => Contact::Safe,
+            };
+            (board.wrap((self.x + dx) as i32), contact)
+        })
+    }
+
+    fn supports(&self, x: u16, y: u16, board: Board) -> bool {
+        let dx = board.wrap(x as i32 - self.x as i32);
+        dx < self.width && y == self.y && self.dive_phase() != DivePhase::Submerged
+    }
+
+    fn r#move(&mut self, board: Board) {
+        self.x = board.wrap(self.x as i32 + self.speed as i32);
+    }
+
+    /// Runs the dive cycle on by `elapsed` milliseconds. Platforms that don't dive ignore it.
+    fn advance_dive(&mut self, elapsed: u64) {
+        if let Some(clock) = &mut self.dive_clock {
+            *clock = (*clock + elapsed) % DIVE_CYCLE;
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum HostileKind {
+    /// Slithers back and forth along the median or a log. Only its head bites.
+    Snake,
+    /// Waits out of sight, then darts across a river row from one bank.
+    Otter,
+}
+
+/// A creature that hunts the frog rather than just getting in its way.
+pub struct Hostile {
+    pub kind: HostileKind,
+    x: u16,
+    pub y: u16,
+    width: u16,
+    heading: i16,        // One cell per step, left or right
+    clock: u64,          // Milliseconds towards the next step
+    host: Option<usize>, // Log a snake rides, as an index into the platforms
+    offset: u16,         // A snake's position along its log, or along the median
+    lurking: bool,       // An otter off the board, waiting to attack again
+}
+
+impl Hostile {
+    fn snake(y: u16, host: Option<usize>) -> Self {
+        Hostile {
+            kind: HostileKind::Snake,
+            x: 0,
+            y,
+            width: SNAKE_WIDTH,
+            heading: 1,
+            clock: 0,
+            host,
+            offset: 0,
+            lurking: false,
+        }
+    }
+
+    fn otter() -> Self {
+        Hostile {
+            kind: HostileKind::Otter,
+            x: 0,
+            y: GOAL_ROW, // Moved down a row before the first attack
+            width: 1,
+            heading: -1, // Flipped before the first attack, which comes from the left
+            clock: 0,
+            host: None,
+            offset: 0,
+            lurking: true,
+        }
+    }
+
+    /// Board columns covered, each with what touching it does. Snakes are harmless but for
+    /// the head; all of an otter bites. A lurking otter covers nothing.
+    pub fn cells(&self, board: Board) -> impl Iterator<Item = (u16, Contact)> + '_ {
+        let width = if self.lurking { 0 } else { self.width };
+        let head = if self.heading > 0 {
+            width.saturating_sub(1)
+        } else {
+            0
+        };
+        (0..width).map(move |dx| {
+            let contact = match self.kind {
+                HostileKind::Snake if dx != head => Contact::Safe,
+                _ => Contact::Deadly,
+            };
+            (board.wrap((self.x + dx) as i32), contact)
+        })
+    }
+
+    pub fn heading_right(&self) -> bool {
+        self.heading > 0
+    }
+
+    /// Runs the creature on by `elapsed` milliseconds. Call after the platforms have moved,
+    /// so a riding snake keeps its place on its log.
+    fn r#move(&mut self, board: Board, platforms: &[Platform], elapsed: u64) {
+        self.clock += elapsed;
+        match self.kind {
+            HostileKind::Snake => {
+                let room = match self.host {
+                    Some(host) => platforms[host].width,
+                    None => board.width,
+                };
+                while self.clock >= SNAKE_STEP_TIME {
+                    self.clock -= SNAKE_STEP_TIME;
+                    // Turn round at either end of the log or median.
+                    let next = self.offset as i32 + self.heading as i32;
+                    if next < 0 || next + self.width as i32 > room as i32 {
+                        self.heading = -self.heading;
+                    }
+                    self.offset = (self.offset as i32 + self.heading as i32) as u16;
+                }
+                self.x = match self.host {
+                    Some(host) => board.wrap((platforms[host].x + self.offset) as i32),
+                    None => self.offset,
+                };
+            }
+            HostileKind::Otter if self.lurking => {
+                // Each attack comes from the other bank, one river row further down,
+                // so the board stays determined by the seed alone.
+                if self.clock >= OTTER_REST_TIME {
+                    self.clock = 0;
+                    self.lurking = false;
+                    self.y = if self.y + 1 < board.median_row() {
+                        self.y + 1
+                    } else {
+                        GOAL_ROW + 1
+                    };
+                    self.heading = -self.heading;
+                    self.x = if self.heading > 0 { 0 } else { board.width - 1 };
+                }
+            }
+            HostileKind::Otter => {
+                while self.clock >= OTTER_STEP_TIME {
+                    self.clock -= OTTER_STEP_TIME;
+                    let next = self.x as i32 + self.heading as i32;
+                    if next < 0 || next >= board.width as i32 {
+                        // Gone under at the far bank.
+                        self.clock = 0;
+                        self.lurking = true;
+                        break;
+                    }
+                    self.x = next as u16;
+                }
+            }
+        }
+    }
+}
+
+/// One stretch of traffic. Every obstacle in a lane shares its direction and speed, and
+/// obstacles are spaced evenly so the gaps between them never close. Lanes carrying
+/// multi-row hazards span `obstacle_height` rows starting at `y`.
+struct Lane {
+    y: u16,
+    speed: i16,
+    kind: VehicleKind,
+    obstacle_height: u16,
+    count: usize,
+}
+
+impl Lane {
+    fn new(y: u16, speed: i16, kind: VehicleKind, obstacle_height: u16) -> Self {
+        Lane {
+            y,
+            speed,
+            kind,
+            obstacle_height,
+            count: 0,
+        }
+    }
+
+    fn spawn(&self, offset: u16, board: Board) -> Vec<Obstacle> {
+        // Never pack in more obstacles than leave MIN_GAP free cells after each one.
+        let capacity = (board.width / (self.kind.width() + MIN_GAP)) as usize;
+        let count = self.count.min(capacity);
+        if count == 0 {
+            return Vec::new();
+        }
+
+        let spacing = board.width / count as u16;
+        (0..count as u16)
+            .map(|i| {
+                let x = board.wrap((offset + i * spacing) as i32);
+                Obstacle::new(x, self.y, self.obstacle_height, self.speed, self.kind)
+            })
+            .collect()
+    }
+}
+
+fn generate_lanes(settings: &Settings, step: &LevelStep, rng: &mut impl Rng) -> Vec<Lane> {
+    let board = settings.board;
+    let mut lanes = Vec::new();
+
+    let mut y = board.median_row() + 1;
+    while y < board.start_row() {
+        // Occasionally merge two road rows into one lane of two-row trucks,
+        // as long as that doesn't spill onto the start row.
+        let (kind, height) =
+            if step.trucks && y + 1 < board.start_row() && rng.gen_ratio(1, BIG_HAZARD_ODDS) {
+                (VehicleKind::Truck, 2)
+            } else if step.racing_cars && rng.gen_ratio(1, RACING_CAR_ODDS) {
+                (VehicleKind::RacingCar, 1)
+            } else if rng.gen_ratio(1, BULLDOZER_ODDS) {
+                (VehicleKind::Bulldozer, 1)
+            } else {
+                (VehicleKind::Car, 1)
+            };
+        // Neighbouring lanes run in opposite directions, as on a real road.
+        let direction = if lanes.len() % 2 == 0 { 1 } else { -1 };
+        let speed = kind
+            .speed(settings.max_speed, rng)
+            .min(board.width as i16 - 1);
+        lanes.push(Lane::new(y, direction * speed, kind, height));
+        y += height;
+    }
+
+    // Hand the obstacles out round-robin over the lanes, in random order,
+    // so spare lanes are left empty rather than always the same ones.
+    let mut order: Vec<usize> = (0..lanes.len()).collect();
+    order.shuffle(rng);
+    for i in 0..settings.num_obstacles {
+        lanes[order[i % order.len()]].count += 1;
+    }
+
+    lanes
+}
+
+fn generate_obstacles(settings: &Settings, step: &LevelStep, rng: &mut impl Rng) -> Vec<Obstacle> {
+    let board = settings.board;
+    generate_lanes(settings, step, rng)
+        .iter()
+        .flat_map(|lane| lane.spawn(rng.gen_range(0..board.width), board))
+        .collect()
+}
+
+/// Fills every river row with logs and turtles. Rows alternate between the two and
+/// between drifting left and right, with a short stretch of open water between platforms.
+/// Diving turtle groups start at random points in their cycle so they don't dive together.
+fn generate_platforms(settings: &Settings, step: &LevelStep, rng: &mut impl Rng) -> Vec<Platform> {
+    let board = settings.board;
+    let mut platforms = Vec::new();
+
+    for y in GOAL_ROW + 1..board.median_row() {
+        let direction = if y % 2 == 0 { 1 } else { -1 };
+        let speed = direction * rng.gen_range(1..=settings.max_speed);
+        let (kind, width) = if y % 2 == 0 {
+            (PlatformKind::Log, rng.gen_range(3..=5))
+        } else {
+            (PlatformKind::Turtle, rng.gen_range(2..=3))
+        };
+        let spacing = width + rng.gen_range(MIN_GAP..=MIN_GAP + 1);
+        let offset = rng.gen_range(0..board.width);
+        for i in 0..board.width / spacing {
+            let x = board.wrap((offset + i * spacing) as i32);
+            let mut platform = Platform::new(x, y, width, speed, kind);
+            match kind {
+                PlatformKind::Turtle => {
+                    if step.diving_turtles && rng.gen_ratio(1, DIVING_TURTLE_ODDS) {
+                        platform.dive_clock = Some(rng.gen_range(0..DIVE_CYCLE));
+                    }
+                }
+                PlatformKind::Log => {
+                    if step.crocodiles && rng.gen_ratio(1, CROCODILE_ODDS) {
+                        platform.kind = PlatformKind::Crocodile;
+                    }
+                }
+                PlatformKind::Crocodile => {}
+            }
+            platforms.push(platform);
+        }
+    }
+
+    platforms
+}
+
+/// Puts a snake on the median, perhaps another on a log, and an otter in the river, as
+/// far as the level allows.
+fn generate_hostiles(
+    settings: &Settings,
+    step: &LevelStep,
+    platforms: &[Platform],
+    rng: &mut impl Rng,
+) -> Vec<Hostile> {
+    let mut hostiles = Vec::new();
+    if step.snakes {
+        hostiles.push(Hostile::snake(settings.board.median_row(), None));
+        let logs: Vec<usize> = (0..platforms.len())
+            .filter(|&i| matches!(platforms[i].kind, PlatformKind::Log))
+            .filter(|&i| platforms[i].width > SNAKE_WIDTH)
+            .collect();
+        if let Some(&log) = logs.choose(rng) {
+            if rng.gen_ratio(1, RIDING_SNAKE_ODDS) {
+                hostiles.push(Hostile::snake(platforms[log].y, Some(log)));
+            }
+        }
+    }
+    if step.otters {
+        hostiles.push(Hostile::otter());
+    }
+    hostiles
+}
+
+fn platform_under<'a>(frog: &Frog, platforms: &'a [Platform]) -> Option<&'a Platform> {
+    platforms
+        .iter()
+        .find(|platform| platform.supports(frog.x, frog.y, frog.board))
+}
+
+fn check_collision(frog: &Frog, obstacles: &[Obstacle]) -> bool {
+    obstacles
+        .iter()
+        .any(|obstacle| obstacle.occupies(frog.x, frog.y, frog.board))
+}
+
+/// Whether any obstacle went through the frog's cell on its last move.
+fn check_run_over(frog: &Frog, obstacles: &[Obstacle]) -> bool {
+    obstacles
+        .iter()
+        .any(|obstacle| obstacle.swept(frog.x, frog.y, frog.board))
+}
+
+#[derive(Clone, Copy)]
+pub enum Input {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// Something that happened during a `GameState::step`, for the front end to react to.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum GameEvent {
+    HitByTraffic,
+    Bitten,
+    Drowned,
+    HitHedge,
+    CarriedOff,
+    ReachedGoal,
+    OutOfTime,
+    ExtraLife,
+    LevelCleared,
+    GameOver,
+}
+
+/// Everything the rules need to advance the game, with no terminal attached.
+pub struct GameState {
+    pub settings: Settings,
+    pub frog: Frog,
+    pub obstacles: Vec<Obstacle>,
+    pub platforms: Vec<Platform>,
+    pub hostiles: Vec<Hostile>,
+    pub level: u32,
+    pub seed: u64,
+    pub high_score: u32,
+    pub bays: [bool; NUM_BAYS], // Which home bays already hold a frog
+
+    round_elapsed: u64, // Milliseconds
+    rng: StdRng,
+}
+
+impl GameState {
+    /// Builds a game whose every board is determined by `seed`.
+    pub fn new(settings: Settings, seed: u64) -> Self {
+        let mut state = GameState {
+            frog: Frog::new(settings.board, settings.lives),
+            obstacles: Vec::new(),
+            platforms: Vec::new(),
+            hostiles: Vec::new(),
+            level: 1,
+            seed,
+            high_score: 0,
+            bays: [false; NUM_BAYS],
+            round_elapsed: 0,
+            rng: StdRng::seed_from_u64(seed),
+            settings,
+        };
+        state.regenerate();
+        state
+    }
+
+    /// Starts a fresh game with the same settings and seed, so the boards repeat.
+    pub fn restart(&mut self) {
+        self.rng = StdRng::seed_from_u64(self.seed);
+        self.frog = Frog::new(self.settings.board, self.settings.lives);
+        self.level = 1;
+        self.bays = [false; NUM_BAYS];
+        self.round_elapsed = 0;
+        self.regenerate();
+    }
+
+    /// Starts a fresh game on the boards `seed` determines.
+    pub fn reseed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.restart();
+    }
+
+    /// Swaps in new settings and starts over under them.
+    pub fn reconfigure(&mut self, settings: Settings) {
+        self.settings = settings;
+        self.restart();
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.frog.is_dead()
+    }
+
+    /// Whole seconds left on the clock for the current attempt.
+    pub fn remaining_time(&self) -> u64 {
+        self.remaining_millis() / 1000
+    }
+
+    /// What is left of the current attempt's time as a fraction, from 1 down to 0.
+    pub fn remaining_fraction(&self) -> f64 {
+        self.remaining_millis() as f64 / (self.settings.round_time * 1000) as f64
+    }
+
+    fn remaining_millis(&self) -> u64 {
+        (self.settings.round_time * 1000).saturating_sub(self.round_elapsed)
+    }
+
+    /// Advances the game by one tick, applying `inputs` first, in order. Does nothing once
+    /// the game is over.
+    pub fn step(&mut self, inputs: &[Input]) -> Vec<GameEvent> {
+        let mut events = Vec::new();
+        if self.is_over() {
+            return events;
+        }
+
+        // Every cell the frog hops through on the way is checked, so several moves in one
+        // tick can't skip over traffic or water. Where it ends up is checked once the world
+        // has moved, below. Landing on the goal row ends its moves for the tick.
+        let mut death = None;
+        for (i, &input) in inputs.iter().enumerate() {
+            if i > 0 {
+                death = self.check_hazards();
+                if death.is_some() || self.frog.y == GOAL_ROW {
+                    break;
+                }
+            }
+            self.frog.hop(input);
+        }
+
+        // A platform carries the frog along with it; riding off the board is fatal.
+        let ride = match death {
+            Some(_) => None,
+            None => platform_under(&self.frog, &self.platforms).map(|platform| platform.speed),
+        };
+        let board = self.settings.board;
+        for platform in &mut self.platforms {
+            platform.r#move(board);
+            platform.advance_dive(self.settings.frame_rate);
+        }
+        for obstacle in &mut self.obstacles {
+            obstacle.r#move(board);
+        }
+        for hostile in &mut self.hostiles {
+            hostile.r#move(board, &self.platforms, self.settings.frame_rate);
+        }
+
+        self.round_elapsed += self.settings.frame_rate;
+
+        if let Some(death) = death.or_else(|| self.check_death(ride)) {
+            match self.settings.mode {
+                GameMode::Classic => self.frog.lose_life(),
+                GameMode::Practice => self.frog.reset_position(),
+            }
+            self.round_elapsed = 0;
+            events.push(death);
+        }
+
+        self.handle_progress(&mut events);
+        self.handle_goal(&mut events);
+
+        self.high_score = self.high_score.max(self.frog.score);
+
+        if self.frog.is_dead() {
+            events.push(GameEvent::GameOver);
+        }
+
+        events
+    }
+
+    /// Rolls a new board for the current level, scaled up from the preset along
+    /// `LEVEL_CURVE`. Speeds stay below the board width so nothing skips a whole lap.
+    fn regenerate(&mut self) {
+        let step = level_step(self.level);
+        let mut settings = self.settings;
+        settings.num_obstacles += step.extra_obstacles;
+        settings.max_speed = (settings.max_speed + step.extra_speed)
+            .min(settings.board.width as i16 - 1)
+            .max(1);
+        self.obstacles = generate_obstacles(&settings, step, &mut self.rng);
+        self.platforms = generate_platforms(&settings, step, &mut self.rng);
+        self.hostiles = generate_hostiles(&settings, step, &self.platforms, &mut self.rng);
+    }
+
+    /// Works out whether the frog dies this tick: carried off the board by the platform it
+    /// was riding, run over by traffic passing through its cell, killed where it stands, or
+    /// out of time.
+    fn check_death(&mut self, ride: Option<i16>) -> Option<GameEvent> {
+        if ride.is_some_and(|speed| !self.frog.drift(speed)) {
+            Some(GameEvent::CarriedOff)
+        } else if check_run_over(&self.frog, &self.obstacles) {
+            Some(GameEvent::HitByTraffic)
+        } else if let Some(death) = self.check_hazards() {
+            Some(death)
+        } else if self.remaining_millis() == 0 {
+            Some(GameEvent::OutOfTime)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the frog's cell kills it: hit by traffic, bitten, in the river without a
+    /// platform under it, or on the goal row anywhere but an empty bay.
+    fn check_hazards(&self) -> Option<GameEvent> {
+        if check_collision(&self.frog, &self.obstacles) {
+            Some(GameEvent::HitByTraffic)
+        } else if self.check_bitten() {
+            Some(GameEvent::Bitten)
+        } else if self.settings.board.is_river_row(self.frog.y)
+            && platform_under(&self.frog, &self.platforms).is_none()
+        {
+            Some(GameEvent::Drowned)
+        } else if self.frog.y == GOAL_ROW && !self.bay_open(self.frog.x) {
+            Some(GameEvent::HitHedge)
+        } else {
+            None
+        }
+    }
+
+    /// Whether the frog shares a cell with a crocodile's head or a snake's or otter's jaws.
+    fn check_bitten(&self) -> bool {
+        let (frog, board) = (&self.frog, self.settings.board);
+        let deadly = |(x, contact): (u16, Contact)| x == frog.x && contact == Contact::Deadly;
+        self.platforms
+            .iter()
+            .filter(|platform| platform.y == frog.y)
+            .any(|platform| platform.cells(board).any(deadly))
+            || self
+                .hostiles
+                .iter()
+                .filter(|hostile| hostile.y == frog.y)
+                .any(|hostile| hostile.cells(board).any(deadly))
+    }
+
+    fn bay_open(&self, x: u16) -> bool {
+        match self.settings.board.bay_at(x) {
+            Some(bay) => !self.bays[bay],
+            None => false,
+        }
+    }
+
+    /// Awards step points the first time the frog reaches each row during the current life.
+    fn handle_progress(&mut self, events: &mut Vec<GameEvent>) {
+        let frog = &mut self.frog;
+        if frog.y < frog.furthest_row {
+            let rows = (frog.furthest_row - frog.y) as u32;
+            frog.furthest_row = frog.y;
+            if frog.add_points(rows * STEP_POINTS, self.settings.extra_life_score) {
+                events.push(GameEvent::ExtraLife);
+            }
+        }
+    }
+
+    /// Fills the bay the frog has landed in and sends it back to the start row. Whatever is
+    /// left of `Settings::round_time` is paid out as a time bonus. Filling the last bay clears
+    /// the level.
+    fn handle_goal(&mut self, events: &mut Vec<GameEvent>) {
+        if self.frog.y != GOAL_ROW {
+            return;
+        }
+        // `check_death` has already dealt with hedges and taken bays.
+        if let Some(bay) = self.settings.board.bay_at(self.frog.x) {
+            self.bays[bay] = true;
+        }
+
+        let bonus = self.remaining_time() as u32 * TIME_BONUS_POINTS;
+        if self
+            .frog
+            .add_points(GOAL_POINTS + bonus, self.settings.extra_life_score)
+        {
+            events.push(GameEvent::ExtraLife);
+        }
+        self.frog.reset_position();
+        self.round_elapsed = 0;
+        events.push(GameEvent::ReachedGoal);
+
+        if self.bays.iter().all(|&filled| filled) {
+            self.level += 1;
+            self.bays = [false; NUM_BAYS];
+            self.regenerate();
+            events.push(GameEvent::LevelCleared);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A medium game on the default board with nothing moving on it, so each test can place
+    /// just what it needs.
+    fn empty_game() -> GameState {
+        let mut state = GameState::new(Settings::for_difficulty(Difficulty::Medium), 7);
+        state.obstacles.clear();
+        state.platforms.clear();
+        state.hostiles.clear();
+        state
+    }
+
+    fn place_frog(state: &mut GameState, x: u16, y: u16) {
+        state.frog.x = x;
+        state.frog.y = y;
+    }
+
+    fn columns(board: Board, available: bool) -> impl Iterator<Item = u16> {
+        (0..board.width).filter(move |&x| board.bay_at(x).is_some() == available)
+    }
+
+    #[test]
+    fn traffic_wrapping_past_the_edge_still_hits() {
+        let mut state = empty_game();
+        let board = state.settings.board;
+        let road = board.median_row() + 1;
+        // A car starting in the last column covers the first columns too.
+        let car = Obstacle::new(board.width - 1, road, 1, 0, VehicleKind::Car);
+        state.obstacles.push(car);
+        place_frog(&mut state, 1, road);
+
+        let events = state.step(&[]);
+        assert!(events.contains(&GameEvent::HitByTraffic));
+        assert_eq!(state.frog.lives, state.settings.lives - 1);
+    }
+
+    #[test]
+    fn fast_traffic_cannot_jump_over_the_frog() {
+        let mut state = empty_game();
+        let road = state.settings.board.median_row() + 1;
+        let racing_car = Obstacle::new(2, road, 1, 6, VehicleKind::RacingCar);
+        state.obstacles.push(racing_car);
+        place_frog(&mut state, 5, road);
+
+        assert!(state.step(&[]).contains(&GameEvent::HitByTraffic));
+
+        // Cells it has already gone past are safe.
+        place_frog(&mut state, 5, road);
+        assert!(!state.step(&[]).contains(&GameEvent::HitByTraffic));
+    }
+
+    #[test]
+    fn open_water_drowns() {
+        let mut state = empty_game();
+        place_frog(&mut state, 3, 1);
+
+        assert!(state.step(&[]).contains(&GameEvent::Drowned));
+    }
+
+    #[test]
+    fn riding_off_the_edge_is_fatal() {
+        let mut state = empty_game();
+        let width = state.settings.board.width;
+        let log = Platform::new(width - 3, 1, 3, 1, PlatformKind::Log);
+        state.platforms.push(log);
+        place_frog(&mut state, width - 1, 1);
+
+        assert!(state.step(&[]).contains(&GameEvent::CarriedOff));
+    }
+
+    #[test]
+    fn sinking_turtles_hold_the_frog_up_until_they_submerge() {
+        for (clock, drowned) in [(SURFACED_TIME, false), (SURFACED_TIME + SINKING_TIME, true)] {
+            let mut state = empty_game();
+            let mut turtles = Platform::new(2, 1, 3, 0, PlatformKind::Turtle);
+            turtles.dive_clock = Some(clock);
+            state.platforms.push(turtles);
+            place_frog(&mut state, 3, 1);
+            assert_eq!(state.step(&[]).contains(&GameEvent::Drowned), drowned);
+        }
+    }
+
+    #[test]
+    fn only_a_crocodiles_head_bites() {
+        // Heading right, so its head is the rightmost cell. The frog rides along with it.
+        for (x, bitten) in [(3, false), (5, true)] {
+            let mut state = empty_game();
+            state
+                .platforms
+                .push(Platform::new(2, 1, 4, 1, PlatformKind::Crocodile));
+            place_frog(&mut state, x, 1);
+            let events = state.step(&[]);
+            assert_eq!(events.contains(&GameEvent::Bitten), bitten);
+            assert!(!events.contains(&GameEvent::Drowned));
+        }
+    }
+
+    #[test]
+    fn only_a_snakes_head_bites() {
+        // A new snake heads right from the left end of the median, head in its second cell.
+        let mut state = empty_game();
+        let median = state.settings.board.median_row();
+        state.hostiles.push(Hostile::snake(median, None));
+        place_frog(&mut state, 0, median);
+        assert!(state.step(&[]).is_empty());
+        place_frog(&mut state, 1, median);
+        assert!(state.step(&[]).contains(&GameEvent::Bitten));
+
+        // Riding a log, it keeps to the same end of the log as the frog drifts along.
+        for (x, bitten) in [(4, false), (5, true)] {
+            let mut state = empty_game();
+            state
+                .platforms
+                .push(Platform::new(4, 2, 4, 1, PlatformKind::Log));
+            state.hostiles.push(Hostile::snake(2, Some(0)));
+            place_frog(&mut state, x, 2);
+            assert_eq!(state.step(&[]).contains(&GameEvent::Bitten), bitten);
+        }
+    }
+
+    #[test]
+    fn a_lurking_otter_cannot_bite() {
+        let mut state = empty_game();
+        state
+            .platforms
+            .push(Platform::new(0, 1, 5, 0, PlatformKind::Log));
+        let mut otter = Hostile::otter();
+        otter.x = 3;
+        otter.y = 1;
+        state.hostiles.push(otter);
+        place_frog(&mut state, 3, 1);
+        assert!(state.step(&[]).is_empty());
+
+        // Surfaced where it lurked, and not yet due to swim on.
+        let otter = &mut state.hostiles[0];
+        otter.lurking = false;
+        otter.clock = 0;
+        assert!(state.step(&[]).contains(&GameEvent::Bitten));
+    }
+
+    #[test]
+    fn hedges_kill_and_bays_fill() {
+        let mut state = empty_game();
+        let board = state.settings.board;
+
+        let hedge = columns(board, false).next().unwrap();
+        place_frog(&mut state, hedge, 1);
+        assert!(state.step(&[Input::Up]).contains(&GameEvent::HitHedge));
+        assert!(!state.bays.contains(&true));
+
+        let bay = columns(board, true).next().unwrap();
+        place_frog(&mut state, bay, 1);
+        assert!(state.step(&[Input::Up]).contains(&GameEvent::ReachedGoal));
+        assert!(state.bays[board.bay_at(bay).unwrap()]);
+
+        // A bay that is already taken is as deadly as a hedge.
+        place_frog(&mut state, bay, 1);
+        assert!(state.step(&[Input::Up]).contains(&GameEvent::HitHedge));
+    }
+
+    #[test]
+    fn filling_every_bay_clears_the_level() {
+        let mut state = empty_game();
+        let board = state.settings.board;
+        let bays: Vec<u16> = (0..NUM_BAYS)
+            .map(|bay| {
+                columns(board, true)
+                    .find(|&x| board.bay_at(x) == Some(bay))
+                    .unwrap()
+            })
+            .collect();
+
+        for (i, &x) in bays.iter().enumerate() {
+            // Keep the level's fresh traffic out of the way once it is cleared.
+            state.obstacles.clear();
+            state.platforms.clear();
+            state.hostiles.clear();
+            place_frog(&mut state, x, 1);
+            let events = state.step(&[Input::Up]);
+            assert!(events.contains(&GameEvent::ReachedGoal));
+            assert_eq!(events.contains(&GameEvent::LevelCleared), i == NUM_BAYS - 1);
+        }
+        assert_eq!(state.level, 2);
+        assert!(!state.bays.contains(&true));
+    }
+
+    #[test]
+    fn running_out_of_time_costs_a_life() {
+        let mut state = empty_game();
+        state.round_elapsed = state.settings.round_time * 1000 - state.settings.frame_rate;
+
+        assert!(state.step(&[]).contains(&GameEvent::OutOfTime));
+        assert_eq!(state.frog.lives, state.settings.lives - 1);
+        assert_eq!(state.remaining_time(), state.settings.round_time);
+    }
+
+    #[test]
+    fn each_new_row_scores_once_per_life() {
+        let mut state = empty_game();
+        state.step(&[Input::Up]);
+        assert_eq!(state.frog.score, STEP_POINTS);
+
+        // Going back over ground already covered earns nothing.
+        state.step(&[Input::Down]);
+        state.step(&[Input::Up]);
+        assert_eq!(state.frog.score, STEP_POINTS);
+
+        state.step(&[Input::Up]);
+        assert_eq!(state.frog.score, 2 * STEP_POINTS);
+    }
+
+    #[test]
+    fn reaching_a_bay_pays_the_goal_and_time_bonus() {
+        let mut state = empty_game();
+        let bay = columns(state.settings.board, true).next().unwrap();
+        place_frog(&mut state, bay, 1);
+        state.frog.furthest_row = 1;
+
+        assert!(state.step(&[Input::Up]).contains(&GameEvent::ReachedGoal));
+        // One tick into the medium preset's 45 seconds leaves 44 whole seconds.
+        let bonus = 44 * TIME_BONUS_POINTS;
+        assert_eq!(state.frog.score, STEP_POINTS + GOAL_POINTS + bonus);
+    }
+
+    #[test]
+    fn the_extra_life_is_awarded_once() {
+        let mut state = empty_game();
+        state.settings.extra_life_score = 2 * STEP_POINTS;
+        let lives = state.frog.lives;
+
+        assert!(!state.step(&[Input::Up]).contains(&GameEvent::ExtraLife));
+        assert!(state.step(&[Input::Up]).contains(&GameEvent::ExtraLife));
+        assert_eq!(state.frog.lives, lives + 1);
+        assert!(!state.step(&[Input::Up]).contains(&GameEvent::ExtraLife));
+        assert_eq!(state.frog.lives, lives + 1);
+    }
+
+    #[test]
+    fn several_moves_in_one_tick_cannot_cross_open_water() {
+        let mut state = empty_game();
+        let board = state.settings.board;
+        place_frog(&mut state, 3, board.median_row());
+
+        let events = state.step(&[Input::Up; 5]);
+        assert!(events.contains(&GameEvent::Drowned));
+        assert!(!events.contains(&GameEvent::ReachedGoal));
+        assert_eq!(state.frog.y, board.start_row());
+
+        // On safe ground every move still counts.
+        place_frog(&mut state, 3, board.start_row());
+        state.step(&[Input::Right, Input::Right, Input::Up]);
+        assert_eq!((state.frog.x, state.frog.y), (5, board.start_row() - 1));
+    }
+
+    #[test]
+    fn same_seed_and_inputs_replay_the_same_game() {
+        let settings = Settings::for_difficulty(Difficulty::Hard);
+        let mut first = GameState::new(settings, 42);
+        let mut second = GameState::new(settings, 42);
+        let inputs = [Input::Up, Input::Left, Input::Up, Input::Right, Input::Down];
+
+        for tick in 0..200 {
+            let input = [inputs[tick % inputs.len()]];
+            first.step(&input);
+            second.step(&input);
+        }
+
+        let layout = |state: &GameState| -> Vec<(u16, u16, u16, i16)> {
+            state
+                .obstacles
+                .iter()
+                .map(|obstacle| (obstacle.x, obstacle.y, obstacle.width, obstacle.speed))
+                .collect()
+        };
+        assert!(!first.obstacles.is_empty());
+        assert_eq!(layout(&first), layout(&second));
+        assert_eq!((first.frog.x, first.frog.y), (second.frog.x, second.frog.y));
+        assert_eq!(first.frog.score, second.frog.score);
+    }
+}
@@ -1,234 +1,317 @@
+mod app;
+mod game;
+mod render;
+mod scores;
+
+use app::Session;
 use clap::{App, Arg};
 use crossterm::{
     cursor,
-    event::{read, Event, KeyCode, KeyEvent},
-    execute,
-    style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor},
-    terminal,
+    event::{self, Event},
+    execute, terminal,
+};
+use game::{
+    Difficulty, GameMode, GameState, Settings, DEFAULT_WIDTH, MAX_HEIGHT, MAX_WIDTH, MIN_HEIGHT,
+    MIN_WIDTH,
 };
-use rand::Rng;
-use std::io::{stdout, Write};
+use render::Renderer;
+use scores::HighScores;
+use std::io::stdout;
+use std::panic;
 use std::{thread, time};
 
-const WIDTH: u16 = 20;
-const HEIGHT: u16 = 10;
-const FROG_CHAR: char = '0';
-const MAX_LIVES: u8 = 3;
-const NUM_OBSTACLES: usize = 5;
-const FRAME_RATE: u64 = 100; // Milliseconds
-
-struct Frog {
-    x: u16,
-    y: u16,
-    lives: u8,
-}
-
-impl Frog {
-    fn new(x: u16, y: u16, lives: u8) -> Self {
-        Frog { x, y, lives }
-    }
-
-    fn move_up(&mut self) {
-        if self.y > 0 {
-            self.y -= 1;
-        }
-    }
-
-    fn move_down(&mut self) {
-        if self.y < HEIGHT - 1 {
-            self.y += 1;
-        }
-    }
-
-    fn move_left(&mut self) {
-        if self.x > 0 {
-            self.x -= 1;
-        }
-    }
-
-    fn move_right(&mut self) {
-        if self.x < WIDTH - 1 {
-            self.x += 1;
-        }
+fn present(renderer: &mut Renderer) {
+    match renderer.present() {
+        Ok(_) => (),
+        Err(e) => eprintln!("Failed to draw frame: {}", e),
     }
 }
 
-struct Obstacle {
-    x: u16,
-    y: u16,
-    width: u16,
-    height: u16,
-    speed: i16,
-}
-
-impl Obstacle {
-    fn new(x: u16, y: u16, width: u16, height: u16, speed: i16) -> Self {
-        Obstacle {
-            x,
-            y,
-            width,
-            height,
-            speed,
-        }
-    }
-
-    fn draw(&self) {
-        match execute!(
-            stdout(),
-            cursor::MoveTo(self.x, self.y),
-            SetBackgroundColor(Color::Red),
-            SetForegroundColor(Color::White),
-            Print("#"),
-            ResetColor
-        ) {
-            Ok(_) => (),
-            Err(e) => eprintln!("Failed to draw obstacle: {}", e),
-        }
-    }
-
-    fn clear(&self) {
-        match execute!(stdout(), cursor::MoveTo(self.x, self.y), Print(" ")) {
-            Ok(_) => (),
-            Err(e) => eprintln!("Failed to clear obstacle: {}", e),
-        }
-    }
+/// Keeps the terminal in raw mode on the alternate screen for as long as it is alive.
+struct TerminalCleanup;
 
-    fn r#move(&mut self) {
-        self.x = ((self.x as i32) + self.speed as i32).rem_euclid(WIDTH as i32) as u16;
+impl TerminalCleanup {
+    fn enter() -> crossterm::Result<Self> {
+        terminal::enable_raw_mode()?;
+        // From here on the guard exists, so any failure below still gets undone on drop.
+        let cleanup = TerminalCleanup;
+        execute!(stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(cleanup)
     }
 }
 
-fn generate_obstacles() -> Vec<Obstacle> {
-    let mut obstacles = Vec::new();
-    let mut rng = rand::thread_rng();
-
-    for _ in 0..NUM_OBSTACLES {
-        let x = rng.gen_range(0..WIDTH);
-        let y = rng.gen_range(1..HEIGHT - 1);
-        let width = rng.gen_range(1..4);
-        let height = 1;
-        let speed = rng.gen_range(-2..=2);
-        obstacles.push(Obstacle::new(x, y, width, height, speed));
+impl Drop for TerminalCleanup {
+    fn drop(&mut self) {
+        // Ensure terminal is reset, even in case of panic or early exit
+        restore_terminal();
     }
-
-    obstacles
 }
 
-fn draw_frog(frog: &Frog) {
-    match execute!(stdout(), cursor::MoveTo(frog.x, frog.y), Print(FROG_CHAR)) {
+fn restore_terminal() {
+    match terminal::disable_raw_mode() {
         Ok(_) => (),
-        Err(e) => eprintln!("Failed to draw frog: {}", e),
+        Err(e) => eprintln!("Failed to leave raw mode: {}", e),
     }
-}
-
-fn clear_frog(frog: &Frog) {
-    match execute!(stdout(), cursor::MoveTo(frog.x, frog.y), Print(" ")) {
+    match execute!(stdout(), terminal::LeaveAlternateScreen, cursor::Show) {
         Ok(_) => (),
-        Err(e) => eprintln!("Failed to clear frog: {}", e),
-    }
-}
-
-fn draw_obstacles(obstacles: &[Obstacle]) {
-    for obstacle in obstacles {
-        obstacle.draw();
-    }
-}
-
-fn clear_obstacles(obstacles: &[Obstacle]) {
-    for obstacle in obstacles {
-        obstacle.clear();
-    }
-}
-
-fn check_collision(frog: &Frog, obstacles: &[Obstacle]) -> bool {
-    for obstacle in obstacles {
-        if frog.x >= obstacle.x
-            && frog.x < obstacle.x + obstacle.width
-            && frog.y >= obstacle.y
-            && frog.y < obstacle.y + obstacle.height
-        {
-            return true;
-        }
+        Err(e) => eprintln!("Failed to restore terminal state: {}", e),
     }
-    false
 }
 
-fn handle_collision(frog: &mut Frog, obstacles: &[Obstacle]) {
-    if check_collision(frog, obstacles) {
-        frog.lives -= 1;
-        frog.x = WIDTH / 2;
-        frog.y = HEIGHT - 1;
-    }
-}
-
-struct TerminalCleanup;
-
-impl Drop for TerminalCleanup {
-    fn drop(&mut self) {
-        // Ensure terminal is reset, even in case of panic or early exit
-        match execute!(stdout(), cursor::Show, terminal::Clear(terminal::ClearType::All)) {
-            Ok(_) => (),
-            Err(e) => eprintln!("Failed to restore terminal state: {}", e),
-        }
-    }
+/// Restores the terminal before the default hook prints the panic message, which would
+/// otherwise be lost on the alternate screen. Leaving twice when the guard drops is harmless.
+fn install_panic_hook() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        restore_terminal();
+        default_hook(info);
+    }));
 }
 
 fn main() {
-    let _cleanup = TerminalCleanup;
-
-    let _matches = App::new("Frogger")
+    let matches = App::new("Frogger")
         .arg(
             Arg::with_name("difficulty")
                 .short('d')
                 .long("difficulty")
                 .takes_value(true)
-                .possible_values(&["easy", "medium", "hard"])
+                .possible_values(["easy", "medium", "hard", "custom"])
                 .default_value("medium")
-                .help("Sets the difficulty level: easy, medium, hard"),
+                .help("Sets the difficulty level: easy, medium, hard, custom"),
         )
+        .arg(
+            Arg::with_name("mode")
+                .short('m')
+                .long("mode")
+                .takes_value(true)
+                .possible_values(["classic", "practice"])
+                .default_value("classic")
+                .help("Sets the game mode: classic, practice"),
+        )
+        .arg(
+            Arg::with_name("obstacles")
+                .long("obstacles")
+                .takes_value(true)
+                .value_parser(clap::value_parser!(u16).range(1..))
+                .required_if_eq("difficulty", "custom")
+                .help("Number of obstacles on the board"),
+        )
+        .arg(
+            Arg::with_name("max-speed")
+                .long("max-speed")
+                .takes_value(true)
+                .value_parser(clap::value_parser!(i16).range(1..DEFAULT_WIDTH as i64))
+                .required_if_eq("difficulty", "custom")
+                .help("Fastest an obstacle may move, in cells per tick"),
+        )
+        .arg(
+            Arg::with_name("lives")
+                .long("lives")
+                .takes_value(true)
+                .value_parser(clap::value_parser!(u8).range(1..))
+                .required_if_eq("difficulty", "custom")
+                .help("Number of lives the frog starts with"),
+        )
+        .arg(
+            Arg::with_name("frame-rate")
+                .long("frame-rate")
+                .takes_value(true)
+                .value_parser(clap::value_parser!(u64).range(10..=1000))
+                .required_if_eq("difficulty", "custom")
+                .help("Milliseconds between game ticks"),
+        )
+        .arg(
+            Arg::with_name("time")
+                .long("time")
+                .takes_value(true)
+                .value_parser(clap::value_parser!(u64).range(5..=3600))
+                .help("Seconds the frog has for each life"),
+        )
+        .arg(
+            Arg::with_name("extra-life-at")
+                .long("extra-life-at")
+                .takes_value(true)
+                .value_parser(clap::value_parser!(u32).range(1..))
+                .help("Score at which a bonus life is awarded"),
+        )
+        .arg(
+            Arg::with_name("width")
+                .long("width")
+                .takes_value(true)
+                .value_parser(clap::value_parser!(u16).range(MIN_WIDTH as i64..=MAX_WIDTH as i64))
+                .conflicts_with("fit")
+                .help("Board width in cells"),
+        )
+        .arg(
+            Arg::with_name("height")
+                .long("height")
+                .takes_value(true)
+                .value_parser(clap::value_parser!(u16).range(MIN_HEIGHT as i64..=MAX_HEIGHT as i64))
+                .conflicts_with("fit")
+                .help("Board height in cells"),
+        )
+        .arg(
+            Arg::with_name("fit")
+                .long("fit")
+                .help("Size the board to fill the terminal"),
+        )
+        .arg(
+            Arg::with_name("seed")
+                .long("seed")
+                .takes_value(true)
+                .value_parser(clap::value_parser!(u64))
+                .help("Seed for board generation; every game then replays its layout"),
+        )
+        .subcommand(App::new("scores").about("Prints the saved high score tables"))
         .get_matches();
 
-    let mut frog = Frog::new(WIDTH / 2, HEIGHT - 1, MAX_LIVES);
-    let mut obstacles = generate_obstacles();
+    let (scores, warning) = HighScores::load();
+    if let Some(warning) = warning {
+        eprintln!("{}", warning);
+    }
+    if matches.subcommand_matches("scores").is_some() {
+        scores::print(&scores);
+        return;
+    }
 
-    match execute!(
-        stdout(),
-        terminal::Clear(terminal::ClearType::All),
-        cursor::Hide
-    ) {
-        Ok(_) => (),
+    // Individual values override the chosen preset, which makes the game a custom one;
+    // "custom" requires the first four.
+    let difficulty = matches
+        .value_of("difficulty")
+        .and_then(Difficulty::from_name)
+        .unwrap_or(Difficulty::Medium);
+    let mut settings = Settings::for_difficulty(difficulty);
+    settings.mode = matches
+        .value_of("mode")
+        .and_then(GameMode::from_name)
+        .unwrap_or(GameMode::Classic);
+    let overrides = [
+        "obstacles",
+        "max-speed",
+        "lives",
+        "frame-rate",
+        "time",
+        "extra-life-at",
+    ];
+    if overrides.iter().any(|name| matches.is_present(name)) {
+        settings.difficulty = Difficulty::Custom;
+    }
+    if let Some(&num_obstacles) = matches.get_one::<u16>("obstacles") {
+        settings.num_obstacles = num_obstacles as usize;
+    }
+    if let Some(&max_speed) = matches.get_one::<i16>("max-speed") {
+        settings.max_speed = max_speed;
+    }
+    if let Some(&lives) = matches.get_one::<u8>("lives") {
+        settings.lives = lives;
+    }
+    if let Some(&frame_rate) = matches.get_one::<u64>("frame-rate") {
+        settings.frame_rate = frame_rate;
+    }
+    if let Some(&round_time) = matches.get_one::<u64>("time") {
+        settings.round_time = round_time;
+    }
+    if let Some(&extra_life_score) = matches.get_one::<u32>("extra-life-at") {
+        settings.extra_life_score = extra_life_score;
+    }
+
+    if let Some(&width) = matches.get_one::<u16>("width") {
+        settings.board.width = width;
+    }
+    if let Some(&height) = matches.get_one::<u16>("height") {
+        settings.board.height = height;
+    }
+
+    let (columns, rows) = match terminal::size() {
+        Ok(size) => size,
         Err(e) => {
-            eprintln!("Failed to clear terminal and hide cursor: {}", e);
+            eprintln!("Failed to read terminal size: {}", e);
             return;
         }
+    };
+    if matches.is_present("fit") {
+        settings.board = render::fit_board(columns, rows);
+    }
+    let (min_columns, min_rows) = render::required_size(settings.board);
+    if columns < min_columns || rows < min_rows {
+        eprintln!(
+            "Terminal is {}x{} but a {}x{} board needs at least {}x{}",
+            columns, rows, settings.board.width, settings.board.height, min_columns, min_rows
+        );
+        return;
     }
 
-    loop {
-        draw_frog(&frog);
-        draw_obstacles(&obstacles);
+    let seed = match matches.get_one::<u64>("seed") {
+        Some(&seed) => seed,
+        None => rand::random(),
+    };
+    let game = GameState::new(settings, seed);
+    let mut session = Session::new(game, scores, matches.is_present("seed"));
 
-        if let Ok(Event::Key(KeyEvent { code, .. })) = read() {
-            match code {
-                KeyCode::Char('w') | KeyCode::Up => frog.move_up(),
-                KeyCode::Char('s') | KeyCode::Down => frog.move_down(),
-                KeyCode::Char('a') | KeyCode::Left => frog.move_left(),
-                KeyCode::Char('d') | KeyCode::Right => frog.move_right(),
-                KeyCode::Char('q') => break,
-                _ => {}
-            }
+    install_panic_hook();
+    let cleanup = match TerminalCleanup::enter() {
+        Ok(cleanup) => cleanup,
+        Err(e) => {
+            eprintln!("Failed to set up terminal: {}", e);
+            return;
         }
-
-        clear_frog(&frog);
-        clear_obstacles(&obstacles);
-
-        for obstacle in &mut obstacles {
-            obstacle.r#move();
+    };
+    let board = session.game.settings.board;
+    let mut renderer = Renderer::new(columns, rows, board);
+
+    let mut next_tick = time::Instant::now();
+
+    'game: loop {
+        render::draw_session(&mut renderer, &session);
+        present(&mut renderer);
+
+        // Sleep off whatever is left of this tick so traffic keeps a steady pace
+        // no matter how long input handling and drawing took.
+        // The tick is looked up each time as the title menu can switch difficulty.
+        // Once more than a tick behind (a stalled terminal, a suspended process) start
+        // afresh rather than racing through the missed ticks.
+        let tick = time::Duration::from_millis(session.game.settings.frame_rate);
+        let now = time::Instant::now();
+        if next_tick > now {
+            thread::sleep(next_tick - now);
+        } else if now - next_tick > tick {
+            next_tick = now;
+        }
+        next_tick += tick;
+
+        // Drain every event since the last tick without blocking.
+        let mut inputs = Vec::new();
+        loop {
+            match event::poll(time::Duration::from_millis(0)) {
+                Ok(true) => match event::read() {
+                    Ok(Event::Key(key)) => {
+                        let fits = renderer.fits(board);
+                        let carry_on = session.handle_key(key, &mut inputs, fits);
+                        if !carry_on {
+                            break 'game;
+                        }
+                    }
+                    Ok(Event::Resize(columns, rows)) => {
+                        // Give the player their bearings again before play carries on.
+                        renderer.resize(columns, rows, board);
+                        session.pause();
+                    }
+                    _ => {}
+                },
+                Ok(false) => break,
+                Err(e) => {
+                    eprintln!("Failed to poll for input: {}", e);
+                    break 'game;
+                }
+            }
         }
 
-        handle_collision(&mut frog, &obstacles);
-
-        thread::sleep(time::Duration::from_millis(FRAME_RATE));
+        session.tick(&inputs, renderer.fits(board));
     }
 
-    // The terminal cleanup will automatically restore the cursor and clear the screen.
+    // Leave raw mode and the alternate screen first so anything reported stays readable.
+    drop(cleanup);
+    if let Some(warning) = session.warning {
+        eprintln!("{}", warning);
+    }
 }
@@ -0,0 +1,601 @@
+use crate::app::{PauseItem, Screen, Session, TitleItem};
+use crate::game::{
+    level_step, Board, Contact, DivePhase, GameMode, GameState, HostileKind, PlatformKind,
+    VehicleKind, GOAL_ROW, MAX_HEIGHT, MAX_WIDTH, MIN_HEIGHT, MIN_WIDTH,
+};
+use crate::scores::{HighScores, INITIALS_LEN};
+use crossterm::{
+    cursor, queue,
+    style::{Color, Colors, Print, SetColors},
+    terminal,
+};
+use std::io::{stdout, Write};
+
+const FROG_CHAR: char = '0';
+const LIFE_CHAR: char = '♥';
+const HUD_ROWS: u16 = 5;
+const MAX_LIFE_ICONS: u8 = 10; // More lives than this show as a count
+const HUD_WIDTH: u16 = 26; // Fits "Seed " and the longest u64, and the score row
+const LOGO: [&str; 4] = [
+    r" ___ ___  ___   ___  ___ ___ ___ ",
+    r"| __| _ \/ _ \ / __|/ __| __| _ \",
+    r"| _||   / (_) | (_ | (_ | _||   /",
+    r"|_| |_|_\\___/ \___|\___|___|_|_\",
+];
+
+/// The HUD spans the board, or its own minimum width under a narrow board.
+fn hud_width(board: Board) -> u16 {
+    board.width.max(HUD_WIDTH)
+}
+
+/// Smallest terminal, in columns and rows, that fits `board` and the HUD below it.
+pub fn required_size(board: Board) -> (u16, u16) {
+    (hud_width(board), board.height + HUD_ROWS)
+}
+
+/// Largest board, within the allowed limits, that fits a terminal of the given size.
+/// Never goes below the minimum board, so a too-small terminal still fails `required_size`.
+pub fn fit_board(columns: u16, rows: u16) -> Board {
+    Board::new(
+        columns.clamp(MIN_WIDTH, MAX_WIDTH),
+        rows.saturating_sub(HUD_ROWS).clamp(MIN_HEIGHT, MAX_HEIGHT),
+    )
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Cell {
+    ch: char,
+    fg: Color,
+    bg: Color,
+}
+
+impl Cell {
+    const BLANK: Cell = Cell::new(' ', Color::Reset, Color::Reset);
+
+    const fn new(ch: char, fg: Color, bg: Color) -> Self {
+        Cell { ch, fg, bg }
+    }
+}
+
+/// Double-buffered screen. A frame is composed into the back buffer, then `present` writes
+/// only the cells that differ from what is already on screen and flushes once.
+///
+/// Drawing coordinates are relative to the play field, which is centered in the terminal.
+/// The HUD below it may be wider, so it has its own left edge.
+pub struct Renderer {
+    width: u16,
+    height: u16,
+    origin: (u16, u16),
+    hud_left: u16,
+    front: Vec<Cell>,
+    back: Vec<Cell>,
+    full_redraw: bool,
+}
+
+impl Renderer {
+    /// Sizes the buffers to a `width` x `height` terminal and centers `board` in it.
+    pub fn new(width: u16, height: u16, board: Board) -> Self {
+        let mut renderer = Renderer {
+            width: 0,
+            height: 0,
+            origin: (0, 0),
+            hud_left: 0,
+            front: Vec::new(),
+            back: Vec::new(),
+            full_redraw: true,
+        };
+        renderer.resize(width, height, board);
+        renderer
+    }
+
+    /// Adapts to a new terminal size. Everything is repainted on the next `present`.
+    pub fn resize(&mut self, width: u16, height: u16, board: Board) {
+        let size = width as usize * height as usize;
+        self.width = width;
+        self.height = height;
+        self.front = vec![Cell::BLANK; size];
+        self.back = vec![Cell::BLANK; size];
+        self.full_redraw = true;
+
+        let (needed_width, needed_height) = required_size(board);
+        self.hud_left = width.saturating_sub(needed_width) / 2;
+        self.origin = (
+            self.hud_left + (needed_width - board.width) / 2,
+            height.saturating_sub(needed_height) / 2,
+        );
+    }
+
+    /// Whether the terminal is big enough for `board` and its HUD.
+    pub fn fits(&self, board: Board) -> bool {
+        let (needed_width, needed_height) = required_size(board);
+        self.width >= needed_width && self.height >= needed_height
+    }
+
+    fn get(&self, x: u16, y: u16) -> Cell {
+        let (x, y) = (x + self.origin.0, y + self.origin.1);
+        if x < self.width && y < self.height {
+            self.back[y as usize * self.width as usize + x as usize]
+        } else {
+            Cell::BLANK
+        }
+    }
+
+    /// Sets one cell of the frame being composed. Anything off screen is clipped.
+    fn put(&mut self, x: u16, y: u16, cell: Cell) {
+        self.put_screen(x + self.origin.0, y + self.origin.1, cell);
+    }
+
+    /// Like `put`, but in terminal coordinates rather than play-field ones.
+    fn put_screen(&mut self, x: u16, y: u16, cell: Cell) {
+        if x < self.width && y < self.height {
+            self.back[y as usize * self.width as usize + x as usize] = cell;
+        }
+    }
+
+    fn put_str_screen(&mut self, x: u16, y: u16, text: &str, fg: Color, bg: Color) {
+        for (i, ch) in text.chars().enumerate() {
+            self.put_screen(x + i as u16, y, Cell::new(ch, fg, bg));
+        }
+    }
+
+    /// Writes `text` centered on the terminal row `y`.
+    fn put_str_centered(&mut self, y: u16, text: &str, fg: Color, bg: Color) {
+        let x = self.width.saturating_sub(text.chars().count() as u16) / 2;
+        self.put_str_screen(x, y, text, fg, bg);
+    }
+
+    /// Like `put`, but with `x` counted from the HUD's left edge.
+    fn put_hud(&mut self, x: u16, y: u16, cell: Cell) {
+        self.put_screen(x + self.hud_left, y + self.origin.1, cell);
+    }
+
+    fn put_hud_str(&mut self, x: u16, y: u16, text: &str, fg: Color, bg: Color) {
+        for (i, ch) in text.chars().enumerate() {
+            self.put_hud(x + i as u16, y, Cell::new(ch, fg, bg));
+        }
+    }
+
+    /// Right-aligns `text` in the HUD so it ends just before column `right`.
+    fn put_hud_str_right(&mut self, right: u16, y: u16, text: &str, fg: Color, bg: Color) {
+        let x = right.saturating_sub(text.chars().count() as u16);
+        self.put_hud_str(x, y, text, fg, bg);
+    }
+
+    /// Writes the composed frame to the terminal and starts the next one blank.
+    pub fn present(&mut self) -> crossterm::Result<()> {
+        let mut out = stdout();
+        if self.full_redraw {
+            queue!(out, terminal::Clear(terminal::ClearType::All))?;
+        }
+
+        // Track where the cursor and colors already are to skip redundant escape codes.
+        let mut cursor_at = None;
+        let mut colors = None;
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let i = y as usize * self.width as usize + x as usize;
+                let cell = self.back[i];
+                if !self.full_redraw && cell == self.front[i] {
+                    continue;
+                }
+                if cursor_at != Some((x, y)) {
+                    queue!(out, cursor::MoveTo(x, y))?;
+                }
+                if colors != Some((cell.fg, cell.bg)) {
+                    queue!(out, SetColors(Colors::new(cell.fg, cell.bg)))?;
+                    colors = Some((cell.fg, cell.bg));
+                }
+                queue!(out, Print(cell.ch))?;
+                cursor_at = Some((x + 1, y));
+            }
+        }
+        if colors.is_some() {
+            queue!(out, SetColors(Colors::new(Color::Reset, Color::Reset)))?;
+        }
+        out.flush()?;
+
+        std::mem::swap(&mut self.front, &mut self.back);
+        self.back.fill(Cell::BLANK);
+        self.full_redraw = false;
+        Ok(())
+    }
+}
+
+/// Composes the play field: the home bays and water under the river, then platforms, traffic
+/// and finally the frog so it stays visible on a platform, with the HUD underneath.
+fn draw_game(renderer: &mut Renderer, state: &GameState) {
+    let board = state.settings.board;
+    for x in 0..board.width {
+        let cell = match board.bay_at(x) {
+            Some(bay) if state.bays[bay] => Cell::new(FROG_CHAR, Color::Green, Color::Reset),
+            Some(_) => Cell::BLANK,
+            None => Cell::new(' ', Color::Reset, Color::DarkGreen),
+        };
+        renderer.put(x, GOAL_ROW, cell);
+    }
+    for y in 0..board.height {
+        if board.is_river_row(y) {
+            for x in 0..board.width {
+                renderer.put(x, y, Cell::new(' ', Color::Reset, Color::DarkBlue));
+            }
+        }
+    }
+
+    for platform in &state.platforms {
+        for (x, contact) in platform.cells(board) {
+            let cell = platform_cell(platform.kind, platform.dive_phase(), contact);
+            renderer.put(x, platform.y, cell);
+        }
+    }
+
+    for obstacle in &state.obstacles {
+        let (sprite, color) = vehicle_sprite(obstacle.kind, obstacle.heading_right());
+        for y in obstacle.rows(board) {
+            for (x, ch) in obstacle.columns(board).zip(sprite.chars()) {
+                renderer.put(x, y, Cell::new(ch, color, Color::Reset));
+            }
+        }
+    }
+
+    // Creatures keep whatever they are on, median or log, showing through behind them.
+    for hostile in &state.hostiles {
+        for (x, contact) in hostile.cells(board) {
+            let (ch, fg) = hostile_glyph(hostile.kind, hostile.heading_right(), contact);
+            let under = renderer.get(x, hostile.y);
+            renderer.put(x, hostile.y, Cell::new(ch, fg, under.bg));
+        }
+    }
+
+    let frog = &state.frog;
+    let under = renderer.get(frog.x, frog.y);
+    renderer.put(frog.x, frog.y, Cell::new(FROG_CHAR, Color::Reset, under.bg));
+
+    draw_hud(renderer, state);
+}
+
+/// One cell of a platform. Diving turtles turn red as a warning, then show only as ripples
+/// while under; a crocodile's jaws stand out from its back.
+fn platform_cell(kind: PlatformKind, phase: DivePhase, contact: Contact) -> Cell {
+    match (kind, phase, contact) {
+        (PlatformKind::Log, _, _) => Cell::new('=', Color::White, Color::DarkYellow),
+        (PlatformKind::Turtle, DivePhase::Surfaced, _) => {
+            Cell::new('o', Color::White, Color::DarkGreen)
+        }
+        (PlatformKind::Turtle, DivePhase::Sinking, _) => {
+            Cell::new('o', Color::Red, Color::DarkGreen)
+        }
+        (PlatformKind::Turtle, DivePhase::Submerged, _) => {
+            Cell::new('~', Color::DarkCyan, Color::DarkBlue)
+        }
+        (PlatformKind::Crocodile, _, Contact::Safe) => Cell::new('-', Color::Black, Color::Green),
+        (PlatformKind::Crocodile, _, Contact::Deadly) => Cell::new('W', Color::Red, Color::Green),
+    }
+}
+
+fn hostile_glyph(kind: HostileKind, heading_right: bool, contact: Contact) -> (char, Color) {
+    match (kind, contact) {
+        (HostileKind::Snake, Contact::Safe) => ('~', Color::Green),
+        (HostileKind::Snake, Contact::Deadly) if heading_right => ('>', Color::Green),
+        (HostileKind::Snake, Contact::Deadly) => ('<', Color::Green),
+        (HostileKind::Otter, _) => ('U', Color::Magenta),
+    }
+}
+
+/// Sprite, as wide as the vehicle and drawn from its leftmost column, and its color.
+fn vehicle_sprite(kind: VehicleKind, heading_right: bool) -> (&'static str, Color) {
+    match (kind, heading_right) {
+        (VehicleKind::Car, true) => ("=o>", Color::Yellow),
+        (VehicleKind::Car, false) => ("<o=", Color::Yellow),
+        (VehicleKind::Truck, true) => ("[##>", Color::White),
+        (VehicleKind::Truck, false) => ("<##]", Color::White),
+        (VehicleKind::Bulldozer, true) => ("[=|", Color::DarkYellow),
+        (VehicleKind::Bulldozer, false) => ("|=]", Color::DarkYellow),
+        (VehicleKind::RacingCar, true) => ("=>", Color::Magenta),
+        (VehicleKind::RacingCar, false) => ("<=", Color::Magenta),
+    }
+}
+
+/// Status bar below the play field. The seed is shown so a layout can be reported and
+/// replayed.
+fn draw_hud(renderer: &mut Renderer, state: &GameState) {
+    let board = state.settings.board;
+    let (width, height) = (hud_width(board), board.height);
+    let lives = state.frog.lives;
+    let lives = if lives > MAX_LIFE_ICONS {
+        format!("{}x{}", LIFE_CHAR, lives)
+    } else {
+        LIFE_CHAR.to_string().repeat(lives as usize)
+    };
+    renderer.put_hud_str(0, height, &lives, Color::Red, Color::Reset);
+    draw_time_bar(renderer, state, height + 1);
+
+    let score = format!("Score {}", state.frog.score);
+    renderer.put_hud_str(0, height + 2, &score, Color::Reset, Color::Reset);
+    let level = format!("Level {}", state.level);
+    renderer.put_hud_str_right(width, height + 2, &level, Color::Reset, Color::Reset);
+
+    let high_score = format!("Hi {}", state.high_score);
+    renderer.put_hud_str(0, height + 3, &high_score, Color::Reset, Color::Reset);
+
+    let seed = format!("Seed {}", state.seed);
+    renderer.put_hud_str(0, height + 4, &seed, Color::DarkGrey, Color::Reset);
+}
+
+/// Row `y` of the HUD: a bar that shrinks as the current life's time runs down, turning
+/// red for the last quarter.
+fn draw_time_bar(renderer: &mut Renderer, state: &GameState, y: u16) {
+    const LABEL: &str = "Time ";
+    let length = hud_width(state.settings.board) - LABEL.len() as u16;
+    let fraction = state.remaining_fraction();
+    let filled = (fraction * length as f64).ceil() as u16;
+    let color = if fraction <= 0.25 {
+        Color::Red
+    } else {
+        Color::Green
+    };
+
+    renderer.put_hud_str(0, y, LABEL, Color::Reset, Color::Reset);
+    for i in 0..length {
+        let bg = if i < filled { color } else { Color::DarkGrey };
+        renderer.put_hud(LABEL.len() as u16 + i, y, Cell::new(' ', Color::Reset, bg));
+    }
+}
+
+/// Shown instead of the game while the terminal cannot fit the board.
+fn draw_too_small(renderer: &mut Renderer, board: Board) {
+    let (needed_width, needed_height) = required_size(board);
+    let lines = [
+        "Terminal too small".to_string(),
+        format!("Need {}x{}", needed_width, needed_height),
+    ];
+    for (y, line) in lines.iter().enumerate() {
+        renderer.put_str_screen(0, y as u16, line, Color::Reset, Color::Reset);
+    }
+}
+
+/// Draws `lines` in a box over the middle of the play field, highlighting the line at
+/// `selected` if there is one. A box wider than the board spills evenly over both sides.
+fn draw_panel(renderer: &mut Renderer, board: Board, lines: &[String], selected: Option<usize>) {
+    let inner = lines
+        .iter()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0) as u16;
+    let width = inner + 4;
+    let height = lines.len() as u16 + 2;
+    let left = (renderer.origin.0 + board.width / 2).saturating_sub(width / 2);
+    let top = (renderer.origin.1 + board.height / 2).saturating_sub(height / 2);
+
+    for y in top..top + height {
+        for x in left..left + width {
+            renderer.put_screen(x, y, Cell::new(' ', Color::White, Color::DarkGrey));
+        }
+    }
+    for (i, line) in lines.iter().enumerate() {
+        let (fg, bg) = if selected == Some(i) {
+            (Color::Black, Color::White)
+        } else {
+            (Color::White, Color::DarkGrey)
+        };
+        renderer.put_str_screen(left + 2, top + 1 + i as u16, line, fg, bg);
+    }
+}
+
+/// The logo over the main menu, centered on the whole terminal. Narrow terminals get the
+/// name in plain text instead of the logo.
+fn draw_title(renderer: &mut Renderer, state: &GameState, selected: usize) {
+    let logo_width = LOGO[0].chars().count() as u16;
+    let logo: &[&str] = if renderer.width >= logo_width {
+        &LOGO
+    } else {
+        &["FROGGER"]
+    };
+    let height = logo.len() + 1 + TitleItem::ALL.len() + 1 + 1;
+    let mut y = (renderer.height as usize).saturating_sub(height) as u16 / 2;
+
+    for line in logo {
+        renderer.put_str_centered(y, line, Color::Green, Color::Reset);
+        y += 1;
+    }
+    y += 1;
+    for (i, item) in TitleItem::ALL.iter().enumerate() {
+        let label = format!(" {} ", item.label());
+        let (fg, bg) = if i == selected {
+            (Color::Black, Color::White)
+        } else {
+            (Color::Reset, Color::Reset)
+        };
+        renderer.put_str_centered(y, &label, fg, bg);
+        y += 1;
+    }
+    y += 1;
+    let footer = format!(
+        "{} / {}",
+        state.settings.mode.name(),
+        state.settings.difficulty.name()
+    );
+    renderer.put_str_centered(y, &footer, Color::DarkGrey, Color::Reset);
+}
+
+fn draw_mode_select(renderer: &mut Renderer, board: Board, selected: usize) {
+    let mut lines = vec!["Mode".to_string(), String::new()];
+    lines.extend(
+        GameMode::ALL
+            .iter()
+            .map(|mode| format!("{:<9}{}", mode.name(), mode.description())),
+    );
+    lines.push(String::new());
+    lines.push("Enter to choose".to_string());
+    draw_panel(renderer, board, &lines, Some(selected + 2));
+}
+
+/// The saved table for the difficulty and mode currently selected, if that mode keeps one.
+fn draw_high_scores(renderer: &mut Renderer, state: &GameState, scores: &HighScores) {
+    let settings = &state.settings;
+    let mut lines = vec![
+        format!(
+            "High scores: {} / {}",
+            settings.difficulty.name(),
+            settings.mode.name()
+        ),
+        String::new(),
+    ];
+    let table = scores.table(settings.difficulty, settings.mode);
+    if !settings.mode.keeps_scores() {
+        lines.push("Practice is unscored".to_string());
+    } else if table.is_empty() {
+        lines.push("None yet".to_string());
+    }
+    for (i, entry) in table.iter().enumerate() {
+        lines.push(format!(
+            "{:>2}. {:<3} {:>7}  L{}",
+            i + 1,
+            entry.initials,
+            entry.score,
+            entry.level
+        ));
+    }
+    lines.push(String::new());
+    lines.push("Esc to go back".to_string());
+    draw_panel(renderer, settings.board, &lines, None);
+}
+
+fn draw_help(renderer: &mut Renderer, board: Board) {
+    let lines = [
+        "Help".to_string(),
+        String::new(),
+        "Arrows/WASD  move".to_string(),
+        "P/Esc        pause".to_string(),
+        "Q            quit".to_string(),
+        String::new(),
+        "Cross the road, then ride".to_string(),
+        "logs and turtles over the".to_string(),
+        "river into a home bay.".to_string(),
+        "Fill all five to clear the".to_string(),
+        "level. Traffic, water and".to_string(),
+        "hedges are fatal, and so are".to_string(),
+        "the jaws of crocodiles,".to_string(),
+        "snakes and otters.".to_string(),
+        String::new(),
+        "Esc to go back".to_string(),
+    ];
+    draw_panel(renderer, board, &lines, None);
+}
+
+/// Lists the current settings. Between games the difficulty preset can be changed here.
+fn draw_settings(renderer: &mut Renderer, state: &GameState, editable: bool) {
+    let settings = &state.settings;
+    let difficulty = if editable {
+        format!("< {} >", settings.difficulty.name())
+    } else {
+        settings.difficulty.name().to_string()
+    };
+    let lines = [
+        "Settings".to_string(),
+        String::new(),
+        format!("Difficulty {}", difficulty),
+        format!("Mode       {}", settings.mode.name()),
+        format!(
+            "Board      {}x{}",
+            settings.board.width, settings.board.height
+        ),
+        format!("Obstacles  {}", settings.num_obstacles),
+        format!("Speed      {}", settings.max_speed),
+        format!("Lives      {}", settings.lives),
+        format!("Tick       {}ms", settings.frame_rate),
+        format!("Time       {}s", settings.round_time),
+        String::new(),
+        if editable {
+            "Left/Right change, Esc back".to_string()
+        } else {
+            "Esc to go back".to_string()
+        },
+    ];
+    draw_panel(renderer, settings.board, &lines, None);
+}
+
+fn draw_game_over(renderer: &mut Renderer, state: &GameState) {
+    let lines = [
+        "GAME OVER".to_string(),
+        format!("Final score: {}", state.frog.score),
+        format!("High score: {}", state.high_score),
+        format!("Reached level {}", state.level),
+        format!("Seed: {}", state.seed),
+        String::new(),
+        "r: restart  t: title  q: quit".to_string(),
+    ];
+    draw_panel(renderer, state.settings.board, &lines, None);
+}
+
+fn draw_enter_initials(renderer: &mut Renderer, state: &GameState, initials: &str) {
+    let blanks = "_".repeat(INITIALS_LEN - initials.chars().count());
+    let lines = [
+        "NEW HIGH SCORE".to_string(),
+        format!("Score: {}", state.frog.score),
+        String::new(),
+        format!("Initials: {}{}", initials, blanks),
+        String::new(),
+        "Enter: save  Esc: skip".to_string(),
+    ];
+    draw_panel(renderer, state.settings.board, &lines, None);
+}
+
+fn draw_screen(renderer: &mut Renderer, screen: &Screen, session: &Session) {
+    let state = &session.game;
+    let board = state.settings.board;
+    match screen {
+        Screen::Title { selected } => draw_title(renderer, state, *selected),
+        Screen::ModeSelect { selected } => draw_mode_select(renderer, board, *selected),
+        Screen::HighScores => draw_high_scores(renderer, state, &session.scores),
+        Screen::Help => draw_help(renderer, board),
+        Screen::Playing => draw_game(renderer, state),
+        Screen::Paused { selected } => {
+            draw_game(renderer, state);
+            let lines: Vec<String> = PauseItem::ALL
+                .iter()
+                .map(|item| item.label().to_string())
+                .collect();
+            draw_panel(renderer, board, &lines, Some(*selected));
+        }
+        Screen::Settings { return_to } => {
+            // Opened from the title there is no game behind the panel.
+            let editable = matches!(**return_to, Screen::Title { .. });
+            if !editable {
+                draw_game(renderer, state);
+            }
+            draw_settings(renderer, state, editable);
+        }
+        Screen::ConfirmQuit { return_to } => {
+            draw_screen(renderer, return_to, session);
+            let lines = ["Quit? (y/n)".to_string()];
+            draw_panel(renderer, board, &lines, None);
+        }
+        Screen::LevelComplete { .. } => {
+            draw_game(renderer, state);
+            let mut lines = vec![format!("Level {}", state.level)];
+            let news = level_step(state.level).news;
+            if !news.is_empty() {
+                lines.push(news.to_string());
+            }
+            draw_panel(renderer, board, &lines, None);
+        }
+        Screen::EnterInitials { initials } => {
+            draw_game(renderer, state);
+            draw_enter_initials(renderer, state, initials);
+        }
+        Screen::GameOver => {
+            draw_game(renderer, state);
+            draw_game_over(renderer, state);
+        }
+    }
+}
+
+/// Composes the whole frame for `session`, or a warning if the terminal is too small for it.
+pub fn draw_session(renderer: &mut Renderer, session: &Session) {
+    let board = session.game.settings.board;
+    if renderer.fits(board) {
+        draw_screen(renderer, &session.screen, session);
+    } else {
+        draw_too_small(renderer, board);
+    }
+}
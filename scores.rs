@@ -0,0 +1,333 @@
+use crate::game::{Difficulty, GameMode};
+use std::path::PathBuf;
+use std::{env, fs, io};
+
+pub const TABLE_SIZE: usize = 10; // Entries kept per difficulty and mode
+pub const INITIALS_LEN: usize = 3;
+const FILE_NAME: &str = "scores.tsv";
+
+/// One finished game worth remembering.
+pub struct Entry {
+    pub difficulty: Difficulty,
+    pub mode: GameMode,
+    pub initials: String,
+    pub score: u32,
+    pub level: u32,
+    pub seed: u64,
+}
+
+impl Entry {
+    /// Reads one tab-separated line, or `None` if it is malformed.
+    fn parse(line: &str) -> Option<Self> {
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() != 6 {
+            return None;
+        }
+        let initials = fields[2];
+        if initials.is_empty()
+            || initials.chars().count() > INITIALS_LEN
+            || !initials.chars().all(|ch| ch.is_ascii_alphanumeric())
+        {
+            return None;
+        }
+        Some(Entry {
+            difficulty: Difficulty::from_name(fields[0])?,
+            mode: GameMode::from_name(fields[1])?,
+            initials: initials.to_string(),
+            score: fields[3].parse().ok()?,
+            level: fields[4].parse().ok()?,
+            seed: fields[5].parse().ok()?,
+        })
+    }
+
+    fn to_line(&self) -> String {
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\n",
+            self.difficulty.name(),
+            self.mode.name(),
+            self.initials,
+            self.score,
+            self.level,
+            self.seed
+        )
+    }
+}
+
+/// Where the table lives: `$XDG_DATA_HOME/frogger`, falling back to `~/.local/share/frogger`.
+fn scores_path() -> Option<PathBuf> {
+    let data_home = match env::var_os("XDG_DATA_HOME").map(PathBuf::from) {
+        // The spec says relative paths are invalid and should be ignored.
+        Some(dir) if dir.is_absolute() => dir,
+        _ => PathBuf::from(env::var_os("HOME")?).join(".local/share"),
+    };
+    Some(data_home.join("frogger").join(FILE_NAME))
+}
+
+/// Every table, kept best first. Tables for each difficulty and mode share one file.
+pub struct HighScores {
+    path: Option<PathBuf>,
+    entries: Vec<Entry>,
+}
+
+impl HighScores {
+    /// Reads the saved tables. A missing file is an empty table. A file that can't be read
+    /// is left alone and nothing is saved over it; one with unreadable lines is copied
+    /// aside first, as saving keeps only the lines that could be read. Either way the
+    /// returned warning says what happened.
+    pub fn load() -> (Self, Option<String>) {
+        let mut scores = HighScores {
+            path: None,
+            entries: Vec::new(),
+        };
+        let path = match scores_path() {
+            Some(path) => path,
+            None => {
+                return (
+                    scores,
+                    Some("No home directory; scores won't be saved".into()),
+                )
+            }
+        };
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                scores.path = Some(path);
+                return (scores, None);
+            }
+            Err(e) => {
+                let warning = format!(
+                    "Failed to read {}: {}; scores won't be saved",
+                    path.display(),
+                    e
+                );
+                return (scores, Some(warning));
+            }
+        };
+
+        let skipped = scores.read(&contents);
+        if skipped == 0 {
+            scores.path = Some(path);
+            return (scores, None);
+        }
+        let backup = path.with_extension("tsv.bak");
+        let warning = match fs::copy(&path, &backup) {
+            Ok(_) => {
+                scores.path = Some(path);
+                format!(
+                    "Ignored {} unreadable line(s); the original was kept as {}",
+                    skipped,
+                    backup.display()
+                )
+            }
+            Err(e) => format!(
+                "Ignored {} unreadable line(s) in {} and failed to back it up: {}; \
+                 scores won't be saved",
+                skipped,
+                path.display(),
+                e
+            ),
+        };
+        (scores, Some(warning))
+    }
+
+    /// Adds every well-formed line of `contents`, returning how many others were skipped.
+    fn read(&mut self, contents: &str) -> usize {
+        let mut skipped = 0;
+        for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+            match Entry::parse(line) {
+                Some(entry) => self.insert(entry),
+                None => skipped += 1,
+            }
+        }
+        skipped
+    }
+
+    /// The table for one difficulty and mode, best first.
+    pub fn table(&self, difficulty: Difficulty, mode: GameMode) -> Vec<&Entry> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.difficulty == difficulty && entry.mode == mode)
+            .collect()
+    }
+
+    pub fn best(&self, difficulty: Difficulty, mode: GameMode) -> u32 {
+        self.table(difficulty, mode)
+            .first()
+            .map_or(0, |entry| entry.score)
+    }
+
+    /// Whether `score` would make it onto its table. Modes that keep no scores have none.
+    pub fn qualifies(&self, difficulty: Difficulty, mode: GameMode, score: u32) -> bool {
+        let table = self.table(difficulty, mode);
+        mode.keeps_scores()
+            && score > 0
+            && (table.len() < TABLE_SIZE || table.iter().any(|entry| score > entry.score))
+    }
+
+    /// Adds `entry` below any equal scores, dropping whatever falls off the bottom of its
+    /// table.
+    pub fn insert(&mut self, entry: Entry) {
+        let index = self
+            .entries
+            .iter()
+            .position(|other| other.score < entry.score)
+            .unwrap_or(self.entries.len());
+        let (difficulty, mode) = (entry.difficulty, entry.mode);
+        self.entries.insert(index, entry);
+
+        let mut kept = 0;
+        self.entries.retain(|other| {
+            if other.difficulty != difficulty || other.mode != mode {
+                return true;
+            }
+            kept += 1;
+            kept <= TABLE_SIZE
+        });
+    }
+
+    /// Writes every table back out, replacing the file in one step so a crash mid-write
+    /// cannot leave it half written.
+    pub fn save(&self) -> io::Result<()> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let contents: String = self.entries.iter().map(Entry::to_line).collect();
+        let temp = path.with_extension("tmp");
+        fs::write(&temp, contents)?;
+        fs::rename(&temp, path)
+    }
+}
+
+/// Prints every non-empty table, for `frogger scores`.
+pub fn print(scores: &HighScores) {
+    let mut any = false;
+    for &difficulty in &Difficulty::ALL {
+        for mode in GameMode::ALL.into_iter().filter(|mode| mode.keeps_scores()) {
+            let table = scores.table(difficulty, mode);
+            if table.is_empty() {
+                continue;
+            }
+            if any {
+                println!();
+            }
+            any = true;
+            println!("{} / {}", difficulty.name(), mode.name());
+            for (i, entry) in table.iter().enumerate() {
+                println!(
+                    "{:>2}. {:<3} {:>7}  level {:<3} seed {}",
+                    i + 1,
+                    entry.initials,
+                    entry.score,
+                    entry.level,
+                    entry.seed
+                );
+            }
+        }
+    }
+    if !any {
+        println!("No high scores yet");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(difficulty: Difficulty, initials: &str, score: u32) -> Entry {
+        Entry {
+            difficulty,
+            mode: GameMode::Classic,
+            initials: initials.to_string(),
+            score,
+            level: 1,
+            seed: 0,
+        }
+    }
+
+    fn empty() -> HighScores {
+        HighScores {
+            path: None,
+            entries: Vec::new(),
+        }
+    }
+
+    fn initials(scores: &HighScores, difficulty: Difficulty) -> Vec<&str> {
+        scores
+            .table(difficulty, GameMode::Classic)
+            .iter()
+            .map(|entry| entry.initials.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn parses_a_saved_line() {
+        let line = entry(Difficulty::Hard, "ABC", 120).to_line();
+        let parsed = Entry::parse(line.trim_end()).unwrap();
+        assert!(parsed.difficulty == Difficulty::Hard);
+        assert_eq!(parsed.initials, "ABC");
+        assert_eq!(parsed.score, 120);
+    }
+
+    #[test]
+    fn rejects_malformed_lines() {
+        for line in [
+            "medium\tclassic\tABC\t120\t1",
+            "medium\tclassic\tABC\t120\t1\t5\textra",
+            "medium\tclassic\tABCD\t120\t1\t5",
+            "medium\tclassic\t\t120\t1\t5",
+            "medium\tclassic\tA-B\t120\t1\t5",
+            "medium\tclassic\tABC\tlots\t1\t5",
+            "medium\tclassic\tABC\t-5\t1\t5",
+            "nightmare\tclassic\tABC\t120\t1\t5",
+            "garbage",
+        ] {
+            assert!(Entry::parse(line).is_none(), "accepted {:?}", line);
+        }
+    }
+
+    #[test]
+    fn skips_unreadable_lines_and_keeps_the_rest() {
+        let mut scores = empty();
+        let skipped = scores.read("medium\tclassic\tAB\t120\t1\t5\ngarbage\n\n");
+        assert_eq!(skipped, 1);
+        assert_eq!(initials(&scores, Difficulty::Medium), ["AB"]);
+    }
+
+    #[test]
+    fn ties_go_below_equal_scores() {
+        let mut scores = empty();
+        scores.insert(entry(Difficulty::Medium, "A", 100));
+        scores.insert(entry(Difficulty::Medium, "B", 200));
+        scores.insert(entry(Difficulty::Medium, "C", 100));
+        assert_eq!(initials(&scores, Difficulty::Medium), ["B", "A", "C"]);
+    }
+
+    #[test]
+    fn each_table_is_cut_off_on_its_own() {
+        let mut scores = empty();
+        scores.insert(entry(Difficulty::Easy, "E", 1));
+        for score in 1..=TABLE_SIZE as u32 + 1 {
+            scores.insert(entry(Difficulty::Medium, "M", score * 10));
+        }
+
+        let medium = scores.table(Difficulty::Medium, GameMode::Classic);
+        assert_eq!(medium.len(), TABLE_SIZE);
+        assert_eq!(medium.last().unwrap().score, 20);
+        assert_eq!(initials(&scores, Difficulty::Easy), ["E"]);
+
+        assert!(!scores.qualifies(Difficulty::Medium, GameMode::Classic, 20));
+        assert!(scores.qualifies(Difficulty::Medium, GameMode::Classic, 21));
+        assert!(scores.qualifies(Difficulty::Easy, GameMode::Classic, 1));
+        assert!(!scores.qualifies(Difficulty::Easy, GameMode::Classic, 0));
+    }
+
+    #[test]
+    fn practice_games_are_never_scored() {
+        let scores = empty();
+        assert!(!scores.qualifies(Difficulty::Medium, GameMode::Practice, 500));
+    }
+}